@@ -0,0 +1,338 @@
+use std::fmt::{self, Write as _};
+
+use chumsky::{
+    error::{Rich, RichPattern, RichReason},
+    util::Maybe,
+};
+use parser::{LineIndex, Span};
+
+/// Every diagnostic code emitted by the compiler, kept in one place so they never collide
+pub mod codes {
+    /// The source doesn't match the grammar of the language
+    pub const SYNTAX_ERROR: &str = "E0001";
+    /// There's no `main` function to use as the page
+    pub const MISSING_MAIN: &str = "E0002";
+    /// A value's type isn't the one required where it's used
+    pub const MISMATCHED_TYPES: &str = "E0003";
+    /// A `match` doesn't have an arm for every value it could be matching on
+    pub const NON_EXHAUSTIVE_MATCH: &str = "E0004";
+    /// A `match` arm can never be reached because the arms before it already match everything it
+    /// does
+    pub const UNREACHABLE_PATTERN: &str = "E0005";
+    /// A pattern matches on something that isn't a constructor, like a function
+    pub const UNKNOWN_CONSTRUCTOR: &str = "E0006";
+    /// A type is given more or fewer type arguments than it takes, as in `List` or `String<Int>`
+    pub const WRONG_NUMBER_OF_TYPE_ARGUMENTS: &str = "E0007";
+    /// Something that isn't a type, like a function or an effect, is used as one
+    pub const NOT_A_TYPE: &str = "E0008";
+    /// Something that isn't an effect, like a type, is listed after `eff`
+    pub const NOT_AN_EFFECT: &str = "E0009";
+    /// A call passes more or fewer arguments than what's being called takes
+    pub const WRONG_NUMBER_OF_ARGUMENTS: &str = "E0010";
+    /// An effect reaches `main` without anything that knows how to perform it
+    pub const UNHANDLED_EFFECT: &str = "E0011";
+    /// A function performs an effect that isn't listed after its `eff`, and isn't handled inside of
+    /// it either
+    pub const UNDECLARED_EFFECT: &str = "E0012";
+    /// A `handle` is missing a clause for some of the operations of the effect it handles
+    pub const INCOMPLETE_HANDLER: &str = "E0013";
+    /// A name doesn't refer to anything that's in scope where it's used
+    pub const UNRESOLVED_NAME: &str = "E0014";
+    /// Something that isn't a function, like a type or an effect, is called as one
+    pub const NOT_A_FUNCTION: &str = "E0015";
+    /// What's rendered depends on a value that's only known once the page is running, like what an
+    /// operation from std outputs
+    pub const RUNTIME_VALUE: &str = "E0016";
+    /// `main` takes parameters or type parameters, which nothing is there to give it
+    pub const INVALID_MAIN: &str = "E0017";
+    /// The `resume` of a handler clause is used as a value instead of being called
+    pub const RESUME_NOT_CALLED: &str = "E0018";
+
+    /// A function lists an effect after its `eff` that it never performs
+    pub const UNUSED_EFFECT: &str = "W0001";
+    /// A function is never called from `main`, directly or through other functions
+    pub const UNREACHABLE_FUNCTION: &str = "W0002";
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => f.write_str("error"),
+            Severity::Warning => f.write_str("warning"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: &'static str,
+    pub message: String,
+    /// Where the problem is, this is where the caret underline points
+    pub primary: Label,
+    /// Other places that are related to the problem, such as a previous definition
+    pub secondary: Vec<Label>,
+    pub notes: Vec<String>,
+    pub help: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(severity: Severity, code: &'static str, span: Span, message: String) -> Self {
+        Self {
+            severity,
+            code,
+            message,
+            primary: Label {
+                span,
+                message: String::new(),
+            },
+            secondary: Vec::new(),
+            notes: Vec::new(),
+            help: None,
+        }
+    }
+
+    pub fn error(code: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Error, code, span, message.into())
+    }
+
+    pub fn warning(code: &'static str, span: Span, message: impl Into<String>) -> Self {
+        Self::new(Severity::Warning, code, span, message.into())
+    }
+
+    pub fn with_label(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = message.into();
+        self
+    }
+
+    pub fn with_secondary(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.secondary.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn with_help(mut self, help: impl Into<String>) -> Self {
+        self.help = Some(help.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    pub fn from_parse_error(error: &Rich<'_, char>) -> Self {
+        let span = Span::from(*error.span());
+
+        let expected = describe_expected(error.expected());
+        let found = match error.found() {
+            Some(found) => describe_char(*found),
+            None => "end of input".to_string(),
+        };
+
+        match custom_message(error.reason()) {
+            Some(message) => {
+                let diagnostic = Self::error(codes::SYNTAX_ERROR, span, message);
+                match expected {
+                    Some(expected) => diagnostic.with_note(format!("also expected {expected}")),
+                    None => diagnostic,
+                }
+            }
+            None => match expected {
+                Some(expected) => Self::error(
+                    codes::SYNTAX_ERROR,
+                    span,
+                    format!("expected {expected}, found {found}"),
+                )
+                .with_label(format!("expected {expected}")),
+                None => Self::error(codes::SYNTAX_ERROR, span, format!("unexpected {found}"))
+                    .with_label("unexpected"),
+            },
+        }
+    }
+
+    /// Renders the diagnostic the way it should be shown in a terminal, pointing at the offending
+    /// lines of the source file
+    pub fn render(&self, file_name: &str, index: &LineIndex) -> String {
+        let mut labels = std::iter::once((&self.primary, '^'))
+            .chain(self.secondary.iter().map(|label| (label, '-')))
+            .map(|(label, marker)| (index.line_col(label.span.start), label, marker))
+            .collect::<Vec<_>>();
+        labels.sort_by_key(|(start, _, _)| *start);
+
+        let last_line = labels
+            .iter()
+            .map(|(start, _, _)| start.line + 1)
+            .max()
+            .unwrap_or(1);
+        let gutter = " ".repeat(last_line.to_string().len());
+
+        let mut out = String::new();
+        let primary_start = index.line_col(self.primary.span.start);
+
+        // Writing into a String can't fail
+        let _ = writeln!(out, "{}[{}]: {}", self.severity, self.code, self.message);
+        let _ = writeln!(
+            out,
+            "{gutter}--> {file_name}:{}:{}",
+            primary_start.line + 1,
+            primary_start.column + 1
+        );
+        let _ = writeln!(out, "{gutter} |");
+
+        let mut previous_line = None;
+        for (start, label, marker) in labels {
+            let line = index.line(start.line);
+
+            if previous_line != Some(start.line) {
+                if previous_line.is_some_and(|previous| start.line > previous + 1) {
+                    let _ = writeln!(out, "{gutter}...");
+                }
+                let _ = writeln!(
+                    out,
+                    "{:>width$} | {line}",
+                    start.line + 1,
+                    width = gutter.len()
+                );
+            }
+            previous_line = Some(start.line);
+
+            // Spans running over several lines are only underlined until the end of their first line
+            let end = index.line_col(label.span.end);
+            let line_length = line.chars().count();
+            let width = if end.line == start.line {
+                end.column.saturating_sub(start.column)
+            } else {
+                line_length.saturating_sub(start.column)
+            }
+            .max(1);
+
+            // Tabs are kept so the underline lines up however wide the terminal renders them
+            let indent = line
+                .chars()
+                .take(start.column)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect::<String>();
+            let underline = marker.to_string().repeat(width);
+            let _ = writeln!(
+                out,
+                "{}",
+                format!("{gutter} | {indent}{underline} {}", label.message).trim_end()
+            );
+        }
+
+        if !self.notes.is_empty() || self.help.is_some() {
+            let _ = writeln!(out, "{gutter} |");
+        }
+        for note in &self.notes {
+            let _ = writeln!(out, "{gutter} = note: {note}");
+        }
+        if let Some(help) = &self.help {
+            let _ = writeln!(out, "{gutter} = help: {help}");
+        }
+
+        out
+    }
+}
+
+fn custom_message(reason: &RichReason<'_, char>) -> Option<String> {
+    match reason {
+        RichReason::Custom(message) => Some(message.clone()),
+        RichReason::ExpectedFound { .. } => None,
+        RichReason::Many(many) => many.iter().find_map(custom_message),
+    }
+}
+
+fn describe_char(c: char) -> String {
+    match c {
+        ' ' => "a space".to_string(),
+        '\n' | '\r' => "a new line".to_string(),
+        '\t' => "a tab".to_string(),
+        c if c.is_control() => format!("`{}`", c.escape_debug()),
+        c => format!("`{c}`"),
+    }
+}
+
+/// Describes what the parser expected in plain words such as "one of `(`, `{` or identifier"
+fn describe_expected<'a, 'b: 'a>(
+    expected: impl Iterator<Item = &'a RichPattern<'b, char>>,
+) -> Option<String> {
+    let mut expected = expected
+        .map(|pattern| match pattern {
+            RichPattern::Token(Maybe::Ref(c)) => describe_char(**c),
+            RichPattern::Token(Maybe::Val(c)) => describe_char(*c),
+            RichPattern::Label(label) => label.to_string(),
+            RichPattern::EndOfInput => "end of input".to_string(),
+        })
+        .collect::<Vec<_>>();
+    expected.sort();
+    expected.dedup();
+
+    match &expected[..] {
+        [] => None,
+        [only] => Some(only.clone()),
+        [rest @ .., last] => {
+            let rest = rest.join(", ");
+            if expected.len() == 2 {
+                Some(format!("{rest} or {last}"))
+            } else {
+                Some(format!("one of {rest} or {last}"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::{LineIndex, Span};
+
+    use super::{codes, Diagnostic};
+
+    #[test]
+    fn render() {
+        let source = "fn main() -> Html {\n\tParagraph(\"hi\" {\n}\n";
+        let index = LineIndex::new(source);
+
+        let diagnostic = Diagnostic::error(
+            codes::SYNTAX_ERROR,
+            Span::new(36, 37),
+            "expected `)`, found `{`",
+        )
+        .with_label("expected `)`")
+        .with_secondary(Span::new(30, 31), "to close this")
+        .with_note("arguments are separated by commas")
+        .with_help("add a `)`");
+
+        assert_eq!(
+            diagnostic.render("main.eff", &index),
+            "error[E0001]: expected `)`, found `{`
+ --> main.eff:2:17
+  |
+2 | \tParagraph(\"hi\" {
+  | \t         - to close this
+  | \t               ^ expected `)`
+  |
+  = note: arguments are separated by commas
+  = help: add a `)`
+"
+        );
+    }
+}
@@ -1,20 +1,24 @@
 pub mod writer;
 
+#[derive(Clone)]
 pub struct Program {
     pub body: Vec<StatementOrDeclaration>,
 }
 
+#[derive(Clone)]
 pub enum StatementOrDeclaration {
     Statement(Statement),
     Declaration(Declaration),
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub enum Statement {
     Block(BlockStatement),
     While(WhileStatement),
     If(IfStatement),
     Break(BreakStatement),
+    Return(ReturnStatement),
     Expression(ExpressionStatement),
 }
 
@@ -25,7 +29,9 @@ impl Statement {
 }
 
 pub mod statements {
-    use super::{BlockStatement, BreakStatement, Expression, IfStatement, WhileStatement};
+    use super::{
+        BlockStatement, BreakStatement, Expression, IfStatement, ReturnStatement, WhileStatement,
+    };
 
     pub struct IfStatementBuilder {
         test: Expression,
@@ -49,6 +55,10 @@ pub mod statements {
         BreakStatement
     }
 
+    pub fn return_statement(argument: Expression) -> ReturnStatement {
+        ReturnStatement { argument }
+    }
+
     pub struct WhileStatementBuilder {
         test: Expression,
     }
@@ -67,8 +77,9 @@ pub mod statements {
     }
 }
 
-pub use statements::{break_statement, if_statement, while_statement};
+pub use statements::{break_statement, if_statement, return_statement, while_statement};
 
+#[derive(Clone)]
 pub struct BlockStatement {
     pub body: Vec<StatementOrDeclaration>,
 }
@@ -77,6 +88,7 @@ pub fn block(body: Vec<StatementOrDeclaration>) -> BlockStatement {
     BlockStatement { body }
 }
 
+#[derive(Clone)]
 pub struct WhileStatement {
     pub test: Expression,
     pub body: BlockStatement,
@@ -88,6 +100,7 @@ impl WhileStatement {
     }
 }
 
+#[derive(Clone)]
 pub struct IfStatement {
     pub test: Expression,
     pub consequent: BlockStatement,
@@ -95,11 +108,19 @@ pub struct IfStatement {
 }
 
 impl IfStatement {
+    pub fn alternate(self, alternate: BlockStatement) -> Self {
+        Self {
+            alternate: Some(alternate),
+            ..self
+        }
+    }
+
     pub fn into_statement(self) -> Statement {
         Statement::If(self)
     }
 }
 
+#[derive(Clone)]
 pub struct BreakStatement;
 
 impl BreakStatement {
@@ -108,8 +129,21 @@ impl BreakStatement {
     }
 }
 
+#[derive(Clone)]
+pub struct ReturnStatement {
+    pub argument: Expression,
+}
+
+impl ReturnStatement {
+    pub fn into_statement(self) -> Statement {
+        Statement::Return(self)
+    }
+}
+
+#[derive(Clone)]
 pub struct ExpressionStatement(pub Expression);
 
+#[derive(Clone)]
 pub enum Declaration {
     Variable(VariableDeclaration),
     Function(FunctionDeclaration),
@@ -121,6 +155,7 @@ impl Declaration {
     }
 }
 
+#[derive(Clone)]
 pub struct VariableDeclaration {
     pub kind: VariableDeclarationKind,
     pub declarations: Vec<VariableDeclarator>,
@@ -133,22 +168,24 @@ impl VariableDeclaration {
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub enum VariableDeclarationKind {
     Var,
     Let,
     Const,
 }
 
+#[derive(Clone)]
 pub struct VariableDeclarator {
     pub id: Pattern,
     pub init: Expression,
 }
 
+#[derive(Clone)]
 pub struct FunctionDeclaration {
     pub id: Identifier,
     pub generator: bool,
-    // TODO:
-    pub params: (),
+    pub params: Vec<Pattern>,
     pub body: BlockStatement,
 }
 
@@ -207,14 +244,19 @@ pub mod declare {
     pub struct FunctionDeclarationBuilder {
         id: Identifier,
         generator: bool,
+        params: Vec<Pattern>,
     }
 
     impl FunctionDeclarationBuilder {
+        pub fn params(self, params: Vec<Pattern>) -> Self {
+            Self { params, ..self }
+        }
+
         pub fn body(self, block: BlockStatement) -> FunctionDeclaration {
             FunctionDeclaration {
                 id: self.id,
                 generator: self.generator,
-                params: (),
+                params: self.params,
                 body: block,
             }
         }
@@ -224,15 +266,18 @@ pub mod declare {
         FunctionDeclarationBuilder {
             id,
             generator: true,
+            params: Vec::new(),
         }
     }
 }
 
+#[derive(Clone)]
 pub enum Pattern {
     Ident(Identifier),
     ObjectPattern(ObjectPattern),
 }
 
+#[derive(Clone)]
 pub struct Identifier(pub String);
 
 pub fn ident(id: &str) -> Identifier {
@@ -269,11 +314,21 @@ impl Identifier {
             arguments,
         }
     }
+
+    /// Assigns to a variable that was declared with `let`
+    pub fn assign(self, value: Expression) -> AssignmentExpression {
+        AssignmentExpression {
+            left: self,
+            right: value.boxed(),
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct ObjectPattern {
     pub properties: Vec<ObjectPatternProperty>,
     // TODO:
+    #[allow(dead_code)]
     pub rest: Option<()>,
 }
 
@@ -291,19 +346,23 @@ pub fn obj_pat(props: Vec<(&str, Option<&str>)>) -> Pattern {
     })
 }
 
+#[derive(Clone)]
 pub struct ObjectPatternProperty {
     pub key: Identifier,
     pub value: Option<Identifier>,
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub enum Expression {
     Ident(Identifier),
     Call(CallExpression),
     ArrowFunction(ArrowFunctionExpression),
+    Function(FunctionExpression),
     Literal(LiteralExpression),
     Member(MemberExpression),
     Binary(BinaryExpression),
+    Assignment(AssignmentExpression),
     Yield(YieldExpression),
     Object(ObjectExpression),
     Array(ArrayExpression),
@@ -327,6 +386,13 @@ impl Expression {
         }
     }
 
+    pub fn member_access(self, member: &str) -> MemberExpression {
+        MemberExpression::StaticMemberExpression(StaticMemberExpression {
+            object: self.boxed(),
+            property: Identifier(member.to_owned()),
+        })
+    }
+
     pub fn strict_eq(self, right: Expression) -> Expression {
         Expression::Binary(BinaryExpression {
             left: Box::new(self),
@@ -334,8 +400,27 @@ impl Expression {
             right: Box::new(right),
         })
     }
+
+    /// `&&`, which is only ever used between comparisons so nothing has to be parenthesized
+    pub fn and(self, right: Expression) -> Expression {
+        Expression::Binary(BinaryExpression {
+            left: Box::new(self),
+            operator: BinaryOperator::LogicalAnd,
+            right: Box::new(right),
+        })
+    }
+
+    /// `??`, which falls back to `right` when this is `null` or `undefined`
+    pub fn nullish_or(self, right: Expression) -> Expression {
+        Expression::Binary(BinaryExpression {
+            left: Box::new(self),
+            operator: BinaryOperator::NullishCoalescing,
+            right: Box::new(right),
+        })
+    }
 }
 
+#[derive(Clone)]
 pub struct CallExpression {
     pub callee: Box<Expression>,
     pub arguments: Vec<Expression>,
@@ -346,23 +431,49 @@ impl CallExpression {
         Expression::Call(self)
     }
 
+    #[allow(dead_code)]
     pub fn into_statement(self) -> Statement {
         Statement::Expression(ExpressionStatement(self.into_expression()))
     }
 }
 
+#[derive(Clone)]
 pub struct ArrowFunctionExpression {
     // TODO:
+    #[allow(dead_code)]
     pub params: Vec<()>,
     pub body: BlockStatement,
 }
 
+#[derive(Clone)]
+pub struct FunctionExpression {
+    pub generator: bool,
+    pub body: BlockStatement,
+}
+
+/// An anonymous generator function, which is what blocks that are used as expressions get wrapped
+/// in so they can be immediately called
+pub fn gen_func_expr(body: BlockStatement) -> FunctionExpression {
+    FunctionExpression {
+        generator: true,
+        body,
+    }
+}
+
+impl FunctionExpression {
+    pub fn into_expression(self) -> Expression {
+        Expression::Function(self)
+    }
+}
+
+#[derive(Clone)]
 pub enum LiteralExpression {
     Boolean(BooleanLiteral),
     String(StringLiteral),
     Number(NumberLiteral),
 }
 
+#[derive(Clone)]
 pub enum MemberExpression {
     StaticMemberExpression(StaticMemberExpression),
     ComputedMemberExpression(ComputedMemberExpression),
@@ -388,26 +499,45 @@ impl MemberExpression {
     }
 }
 
+#[derive(Clone)]
 pub struct BinaryExpression {
     pub left: Box<Expression>,
     pub operator: BinaryOperator,
     pub right: Box<Expression>,
 }
 
+#[derive(Clone)]
 pub enum BinaryOperator {
     StrictEqual,
+    NullishCoalescing,
+    LogicalAnd,
 }
 
+#[derive(Clone)]
+pub struct AssignmentExpression {
+    pub left: Identifier,
+    pub right: Box<Expression>,
+}
+
+impl AssignmentExpression {
+    pub fn into_statement(self) -> Statement {
+        Statement::Expression(ExpressionStatement(Expression::Assignment(self)))
+    }
+}
+
+#[derive(Clone)]
 pub struct StaticMemberExpression {
     pub object: Box<Expression>,
     pub property: Identifier,
 }
 
+#[derive(Clone)]
 pub struct ComputedMemberExpression {
     pub object: Box<Expression>,
     pub property: Box<Expression>,
 }
 
+#[derive(Clone)]
 pub enum BooleanLiteral {
     False,
     True,
@@ -427,6 +557,7 @@ pub fn boolean(b: bool) -> BooleanLiteral {
     }
 }
 
+#[derive(Clone)]
 pub struct StringLiteral(pub String);
 
 pub fn string(s: &str) -> StringLiteral {
@@ -440,41 +571,112 @@ impl StringLiteral {
 }
 
 #[allow(dead_code)]
+#[derive(Clone)]
 pub enum NumberLiteral {
-    Integer(u32),
+    /// Integers that can't be represented exactly by a JS number are written as BigInts
+    Integer(i64),
     Float(f64),
 }
 
-pub fn int(n: u32) -> NumberLiteral {
+pub fn int(n: i64) -> NumberLiteral {
     NumberLiteral::Integer(n)
 }
 
+pub fn float(n: f64) -> NumberLiteral {
+    NumberLiteral::Float(n)
+}
+
 impl NumberLiteral {
     pub fn into_expression(self) -> Expression {
         Expression::Literal(LiteralExpression::Number(self))
     }
 }
 
+/// Writes out a float the way JS's `Number.prototype.toString` does, with the fewest digits that
+/// still read back as the same float and exponents like `1e+21` only once it's at least `1e21` or
+/// below `1e-6`
+pub fn number_to_string(float: f64) -> String {
+    if float.is_nan() {
+        return "NaN".to_string();
+    }
+    if float == 0.0 {
+        return "0".to_string();
+    }
+    if float < 0.0 {
+        return format!("-{}", number_to_string(-float));
+    }
+    if float.is_infinite() {
+        return "Infinity".to_string();
+    }
+
+    // Rust writes the same shortest digits, just always with an exponent as in `1.5e-7`
+    let scientific = format!("{float:e}");
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("floats to be written with an exponent");
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    // Where the point goes in the digits, as in the float being `0.{digits} * 10^n`
+    let n = exponent
+        .parse::<i32>()
+        .expect("the exponent to be an integer")
+        + 1;
+
+    if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (int, fraction) = digits.split_at(n as usize);
+        format!("{int}.{fraction}")
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        match rest {
+            "" => format!("{first}e{sign}{}", (n - 1).abs()),
+            rest => format!("{first}.{rest}e{sign}{}", (n - 1).abs()),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct YieldExpression {
     pub argument: Box<Expression>,
+    pub delegate: bool,
 }
 
 pub fn yield_(argument: Expression) -> YieldExpression {
     YieldExpression {
         argument: argument.boxed(),
+        delegate: false,
+    }
+}
+
+/// `yield*` which hands control over to another generator until it returns
+pub fn yield_delegate(argument: Expression) -> YieldExpression {
+    YieldExpression {
+        argument: argument.boxed(),
+        delegate: true,
     }
 }
 
 impl YieldExpression {
+    pub fn into_expression(self) -> Expression {
+        Expression::Yield(self)
+    }
+
+    #[allow(dead_code)]
     pub fn into_statement(self) -> Statement {
         Statement::Expression(ExpressionStatement(Expression::Yield(self)))
     }
 }
 
+#[derive(Clone)]
 pub struct ObjectExpression {
     pub properties: Vec<ObjectProperty>,
 }
 
+#[derive(Clone)]
 pub struct ObjectProperty {
     key: Identifier,
     value: Option<Expression>,
@@ -498,6 +700,7 @@ impl ObjectExpression {
     }
 }
 
+#[derive(Clone)]
 pub struct ArrayExpression {
     pub elements: Vec<Expression>,
 }
@@ -2,15 +2,19 @@ use itertools::{Itertools, Position};
 use std::io;
 
 use super::{
-    ArrayExpression, ArrowFunctionExpression, BinaryExpression, BinaryOperator, BlockStatement,
-    BooleanLiteral, BreakStatement, CallExpression, ComputedMemberExpression, Declaration,
-    Expression, ExpressionStatement, FunctionDeclaration, Identifier, IfStatement,
-    LiteralExpression, MemberExpression, NumberLiteral, ObjectExpression, ObjectPattern,
-    ObjectPatternProperty, ObjectProperty, Pattern, Program, Statement, StatementOrDeclaration,
+    number_to_string, ArrayExpression, ArrowFunctionExpression, AssignmentExpression,
+    BinaryExpression, BinaryOperator, BlockStatement, BooleanLiteral, BreakStatement,
+    CallExpression, ComputedMemberExpression, Declaration, Expression, ExpressionStatement,
+    FunctionDeclaration, FunctionExpression, Identifier, IfStatement, LiteralExpression,
+    MemberExpression, NumberLiteral, ObjectExpression, ObjectPattern, ObjectPatternProperty,
+    ObjectProperty, Pattern, Program, ReturnStatement, Statement, StatementOrDeclaration,
     StaticMemberExpression, StringLiteral, VariableDeclaration, VariableDeclarationKind,
     VariableDeclarator, WhileStatement, YieldExpression,
 };
 
+/// The largest integer a JS number can hold without losing precision, `Number.MAX_SAFE_INTEGER`
+const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
 pub struct EcmaWriter<W> {
     writer: W,
 }
@@ -49,6 +53,7 @@ where
             Statement::While(while_statement) => self.write_while_statement(while_statement),
             Statement::If(if_statement) => self.write_if_statement(if_statement),
             Statement::Break(break_statement) => self.write_break_statement(break_statement),
+            Statement::Return(return_statement) => self.write_return_statement(return_statement),
             Statement::Expression(expression_statement) => {
                 self.write_expression_statement(expression_statement)
             }
@@ -101,6 +106,16 @@ where
         self.writer.write(b"break;")
     }
 
+    fn write_return_statement(&mut self, return_statement: &ReturnStatement) -> io::Result<usize> {
+        let mut bytes_written = 0;
+
+        bytes_written += self.writer.write(b"return ")?;
+        bytes_written += self.write_expression(&return_statement.argument)?;
+        bytes_written += self.writer.write(b";")?;
+
+        Ok(bytes_written)
+    }
+
     fn write_expression_statement(
         &mut self,
         expression_statement: &ExpressionStatement,
@@ -164,10 +179,7 @@ where
     ) -> io::Result<usize> {
         let mut bytes_written = 0;
 
-        bytes_written += match &variable_declarator.id {
-            Pattern::Ident(identifier) => self.write_identifier(identifier)?,
-            Pattern::ObjectPattern(object_pattern) => self.write_object_pattern(object_pattern)?,
-        };
+        bytes_written += self.write_pattern(&variable_declarator.id)?;
         bytes_written += self.writer.write(b"=")?;
         bytes_written += self.write_expression(&variable_declarator.init)?;
 
@@ -189,14 +201,33 @@ where
 
         bytes_written += self.write_identifier(&function_declaration.id)?;
 
-        // TODO: function_declaration.params
-        bytes_written += self.writer.write(b"()")?;
+        bytes_written += self.writer.write(b"(")?;
+        bytes_written += function_declaration
+            .params
+            .iter()
+            .map(|param| {
+                let mut bytes_written = 0;
+
+                bytes_written += self.write_pattern(param)?;
+                bytes_written += self.writer.write(b",")?;
+
+                Ok(bytes_written)
+            })
+            .sum::<io::Result<usize>>()?;
+        bytes_written += self.writer.write(b")")?;
 
         bytes_written += self.write_block_statement(&function_declaration.body)?;
 
         Ok(bytes_written)
     }
 
+    fn write_pattern(&mut self, pattern: &Pattern) -> io::Result<usize> {
+        match pattern {
+            Pattern::Ident(identifier) => self.write_identifier(identifier),
+            Pattern::ObjectPattern(object_pattern) => self.write_object_pattern(object_pattern),
+        }
+    }
+
     fn write_identifier(&mut self, identifier: &Identifier) -> io::Result<usize> {
         self.writer.write(identifier.0.as_bytes())
     }
@@ -238,6 +269,9 @@ where
             Expression::ArrowFunction(arrow_function_expression) => {
                 self.write_arrow_function_expression(arrow_function_expression)
             }
+            Expression::Function(function_expression) => {
+                self.write_function_expression(function_expression)
+            }
             Expression::Literal(literal_expression) => {
                 self.write_literal_expression(literal_expression)
             }
@@ -247,6 +281,9 @@ where
             Expression::Binary(binary_expression) => {
                 self.write_binary_expression(binary_expression)
             }
+            Expression::Assignment(assignment_expression) => {
+                self.write_assignment_expression(assignment_expression)
+            }
             Expression::Yield(yield_expression) => self.write_yield_expression(yield_expression),
             Expression::Object(object_expression) => {
                 self.write_object_expression(object_expression)
@@ -291,6 +328,24 @@ where
         Ok(bytes_written)
     }
 
+    fn write_function_expression(
+        &mut self,
+        function_expression: &FunctionExpression,
+    ) -> io::Result<usize> {
+        let mut bytes_written = 0;
+
+        // Always wrapped in parens so it's never mistaken for a declaration and can be called
+        bytes_written += self.writer.write(b"(function")?;
+        if function_expression.generator {
+            bytes_written += self.writer.write(b"*")?;
+        }
+        bytes_written += self.writer.write(b"()")?;
+        bytes_written += self.write_block_statement(&function_expression.body)?;
+        bytes_written += self.writer.write(b")")?;
+
+        Ok(bytes_written)
+    }
+
     fn write_literal_expression(
         &mut self,
         literal_expression: &LiteralExpression,
@@ -315,7 +370,9 @@ where
         let mut bytes_written = 0;
 
         bytes_written += self.writer.write(br#"""#)?;
-        bytes_written += self.writer.write(string_literal.0.as_bytes())?;
+        bytes_written += self
+            .writer
+            .write(escape_string(&string_literal.0).as_bytes())?;
         bytes_written += self.writer.write(br#"""#)?;
 
         Ok(bytes_written)
@@ -323,8 +380,15 @@ where
 
     fn write_number_literal(&mut self, number_literal: &NumberLiteral) -> io::Result<usize> {
         match number_literal {
+            NumberLiteral::Integer(int) if int.unsigned_abs() > MAX_SAFE_INTEGER => {
+                self.writer.write(format!("{int}n").as_bytes())
+            }
             NumberLiteral::Integer(int) => self.writer.write(int.to_string().as_bytes()),
-            NumberLiteral::Float(float) => self.writer.write(float.to_string().as_bytes()),
+            // `-0` is kept as it's written since JS would show it as `0`
+            NumberLiteral::Float(float) if *float == 0.0 && float.is_sign_negative() => {
+                self.writer.write(b"-0")
+            }
+            NumberLiteral::Float(float) => self.writer.write(number_to_string(*float).as_bytes()),
         }
     }
 
@@ -378,16 +442,35 @@ where
         bytes_written += self.write_expression(&binary_expression.left)?;
         bytes_written += match binary_expression.operator {
             BinaryOperator::StrictEqual => self.writer.write(b"===")?,
+            BinaryOperator::NullishCoalescing => self.writer.write(b"??")?,
+            BinaryOperator::LogicalAnd => self.writer.write(b"&&")?,
         };
         bytes_written += self.write_expression(&binary_expression.right)?;
 
         Ok(bytes_written)
     }
 
+    fn write_assignment_expression(
+        &mut self,
+        assignment_expression: &AssignmentExpression,
+    ) -> io::Result<usize> {
+        let mut bytes_written = 0;
+
+        bytes_written += self.write_identifier(&assignment_expression.left)?;
+        bytes_written += self.writer.write(b"=")?;
+        bytes_written += self.write_expression(&assignment_expression.right)?;
+
+        Ok(bytes_written)
+    }
+
     fn write_yield_expression(&mut self, yield_expression: &YieldExpression) -> io::Result<usize> {
         let mut bytes_written = 0;
 
-        bytes_written += self.writer.write(b"yield ")?;
+        bytes_written += if yield_expression.delegate {
+            self.writer.write(b"yield* ")?
+        } else {
+            self.writer.write(b"yield ")?
+        };
         bytes_written += self.write_expression(&yield_expression.argument)?;
 
         Ok(bytes_written)
@@ -444,3 +527,33 @@ where
         Ok(bytes_written)
     }
 }
+
+/// Escapes a string so that it can be put between double quotes, including making sure it can't
+/// end the `<script>` element it's in when inlined into HTML
+fn escape_string(string: &str) -> String {
+    let mut escaped = String::with_capacity(string.len());
+
+    for (index, c) in string.char_indices() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            // Line terminators in JS even though they aren't in JSON
+            '\u{2028}' => escaped.push_str("\\u2028"),
+            '\u{2029}' => escaped.push_str("\\u2029"),
+            '<' if string[index..]
+                .get(..8)
+                .is_some_and(|rest| rest.eq_ignore_ascii_case("</script"))
+                || string[index..].starts_with("<!--") =>
+            {
+                escaped.push_str("\\x3C")
+            }
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
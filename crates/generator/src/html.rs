@@ -17,9 +17,9 @@ where
     pub fn write_element(&mut self, element: &Element) -> io::Result<usize> {
         let mut bytes_written = 0;
 
-        bytes_written += self.writer.write(&[b'<'])?;
+        bytes_written += self.writer.write(b"<")?;
         bytes_written += self.writer.write(element.name.as_bytes())?;
-        bytes_written += self.writer.write(&[b'>'])?;
+        bytes_written += self.writer.write(b">")?;
 
         bytes_written += element
             .children
@@ -27,9 +27,24 @@ where
             .map(|child| self.write_child(child))
             .sum::<io::Result<usize>>()?;
 
-        bytes_written += self.writer.write(&[b'<', b'/'])?;
+        bytes_written += self.writer.write(b"</")?;
         bytes_written += self.writer.write(element.name.as_bytes())?;
-        bytes_written += self.writer.write(&[b'>'])?;
+        bytes_written += self.writer.write(b">")?;
+
+        Ok(bytes_written)
+    }
+
+    fn write_text(&mut self, text: &str) -> io::Result<usize> {
+        let mut bytes_written = 0;
+
+        for c in text.chars() {
+            bytes_written += match c {
+                '&' => self.writer.write(b"&amp;")?,
+                '<' => self.writer.write(b"&lt;")?,
+                '>' => self.writer.write(b"&gt;")?,
+                c => self.writer.write(c.encode_utf8(&mut [0; 4]).as_bytes())?,
+            };
+        }
 
         Ok(bytes_written)
     }
@@ -37,7 +52,7 @@ where
     fn write_child(&mut self, child: &Child) -> io::Result<usize> {
         match child {
             Child::Element(element) => self.write_element(element),
-            Child::Text(text) => self.writer.write(text.as_bytes()),
+            Child::Text(text) => self.write_text(text),
             Child::Script(program) => {
                 let mut bytes_written = 0;
 
@@ -54,11 +69,36 @@ where
     }
 }
 
+#[derive(Clone)]
 pub struct Element {
     pub name: String,
     pub children: Vec<Child>,
 }
 
+impl Element {
+    /// The `body` among the children of this element, which is added at the end of them when there
+    /// isn't one
+    pub fn body_mut(&mut self) -> &mut Element {
+        let index = self
+            .children
+            .iter()
+            .position(|child| matches!(child, Child::Element(element) if element.name == "body"))
+            .unwrap_or_else(|| {
+                self.children.push(Child::Element(Element {
+                    name: "body".to_string(),
+                    children: Vec::new(),
+                }));
+                self.children.len() - 1
+            });
+
+        match &mut self.children[index] {
+            Child::Element(body) => body,
+            _ => unreachable!("the child at this index is the body"),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub enum Child {
     Element(Element),
     Text(String),
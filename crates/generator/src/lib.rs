@@ -1,139 +1,890 @@
 mod ecma;
 mod html;
 mod machination;
+mod render;
 
-use html::{Child, Element, HtmlWriter};
-use parser::{Expr, FunctionCallExpr, ModuleItem, Statement, AST};
+use std::collections::HashMap;
+
+use diagnostics::Diagnostic;
+use ecma::writer::EcmaWriter;
+use html::{Child, HtmlWriter};
+use hugs::hir::{
+    self, BlockExpr, Expr, Function, FunctionCallExpr, HandleExpr, Hir, Id, IfExpr, MatchExpr,
+    ModuleItem, Pattern, Statement,
+};
+use render::{Renderer, Value};
+
+/// The JS names of the variables in scope while generating JS
+///
+/// Effectful allows shadowing a variable in the same block but JS doesn't allow redeclaring a
+/// `const`, so every variable after the first one with the same name gets a numbered suffix
+#[derive(Default, Clone)]
+struct Locals {
+    names: HashMap<Id, String>,
+    declarations: HashMap<String, usize>,
+}
+
+impl Locals {
+    /// A JS name that's free to be declared, based on `name`
+    fn declare(&mut self, name: &str) -> String {
+        let declarations = self.declarations.entry(name.to_string()).or_default();
+        let js_name = match *declarations {
+            0 => js_name(name),
+            n => format!("{name}${n}"),
+        };
+        *declarations += 1;
+
+        js_name
+    }
+
+    /// Declares the variable with the given id, the JS name it gets is based on its name in `hir`
+    fn bind(&mut self, hir: &Hir, id: Id) -> String {
+        let js_name = self.declare(name(hir, id));
+        self.names.insert(id, js_name.clone());
+        js_name
+    }
+
+    fn get(&self, id: Id) -> Option<&str> {
+        self.names.get(&id).map(|js_name| &js_name[..])
+    }
+}
 
 pub struct Generator;
 
+fn main(hir: &Hir) -> &Function {
+    hir.module
+        .values()
+        .find_map(|item| match item {
+            ModuleItem::Function(function) if function.name == "main" => Some(&**function),
+            _ => None,
+        })
+        .expect("compiling checks there's a main function")
+}
+
 impl Generator {
     pub fn new() -> Self {
         Self
     }
 
-    pub fn generate(&self, ast: &AST) -> String {
-        // TODO: better error handling
-        let main = ast.module.get("main").expect("missing main function");
+    /// Renders the page along with the JS that performs its effects, failing when what's rendered
+    /// depends on something that's only known once the page is running
+    pub fn generate(&self, hir: &Hir) -> Result<String, Vec<Diagnostic>> {
+        let main = main(hir);
+        let mut element = match Renderer::new(hir).render(main)? {
+            Value::Html(element) => element,
+            _ => unreachable!("type checking ensures main returns Html"),
+        };
 
-        let ModuleItem::Function(main) = main;
+        if let Some(scripts) = self.scripts(hir, main) {
+            // Scripts go last so that the page is there by the time they run, pages that aren't
+            // a whole document get them after everything else in them
+            let parent = match &element.name[..] {
+                "html" => element.body_mut(),
+                _ => &mut element,
+            };
+            parent
+                .children
+                .extend(scripts.into_iter().map(Child::Script));
+        }
 
-        // TODO: Handle main return type not being Html
-        // TODO: Handle main not having a last expression
-        let ret = main
-            .body
-            .return_expression
-            .as_ref()
-            .expect("main must have a return value of Html");
+        let mut buf = Vec::new();
+        let mut writer = HtmlWriter::new(&mut buf);
+        writer.write_element(&element).unwrap();
+        Ok(String::from_utf8(buf).unwrap())
+    }
 
-        let machination = if let Some(effect) = &main.output.eff {
-            match effect {
-                parser::Eff::Simple(effect) if effect == "Console" => {
-                    Some(machination::gen_fns::machination())
-                }
-                _ => todo!(),
-            }
-        } else {
-            None
+    /// Generates only the JS that goes in the page, each script on its own line
+    ///
+    /// Pages whose `main` doesn't perform any effects are fully static and have no JS at all
+    pub fn generate_js(&self, hir: &Hir) -> String {
+        let Some(scripts) = self.scripts(hir, main(hir)) else {
+            return String::new();
         };
 
-        let main_code = main
+        let mut buf = Vec::new();
+        for script in scripts {
+            let mut writer = EcmaWriter::new(&mut buf);
+            writer.write_program(&script).unwrap();
+            buf.push(b'\n');
+        }
+        String::from_utf8(buf).unwrap()
+    }
+
+    /// The functions of the program followed by the runtime that performs their effects, `None`
+    /// when main has no effects to perform
+    fn scripts(&self, hir: &Hir, main: &Function) -> Option<[ecma::Program; 2]> {
+        if main.output.effects.is_empty() {
+            return None;
+        }
+
+        // The runtime only needs to support the effects main is allowed to perform
+        let mut effects = main
+            .output
+            .effects
+            .iter()
+            .map(|(effect, _)| effect)
+            .collect::<Vec<_>>();
+        // So that the order they're listed in doesn't change the output
+        effects.sort_unstable_by_key(|&effect| name(hir, effect));
+        effects.dedup();
+        let machination =
+            machination::gen_fns::machination(&function_js_name(&main.name), &effects);
+
+        let mut locals = Locals::default();
+        let mut main_code = main
             .body
             .statements
             .iter()
-            .map(|statement| self.stmt_to_js(statement))
-            .map(|statement| statement.or_declaration())
+            .map(|statement| self.stmt_to_js(hir, &mut locals, statement))
             .collect::<Vec<_>>();
-
-        let mut element = match self.expr_to_html(ret) {
-            Child::Element(element) => element,
-            Child::Text(_) | Child::Script(_) => {
-                unreachable!("We should verify the type is Html not a string")
+        // main is only responsible for the effects, its return value was already rendered so it's
+        // only there for the effects performed while working it out
+        if let Some(ret) = &main.body.return_expression {
+            if performs(hir, ret, &[]) {
+                main_code.push(
+                    self.expr_to_js(hir, &locals, ret)
+                        .into_statement()
+                        .or_declaration(),
+                );
             }
-        };
+        }
 
-        if let Some(machination) = machination {
-            assert!(element.name == "html");
-            let body = element.children.iter_mut().find_map(|child| match child {
-                Child::Element(element) if element.name == "body" => Some(element),
-                _ => None,
-            });
+        let main_fn = ecma::declare::gen_func(ecma::ident(&function_js_name(&main.name)))
+            .body(ecma::block(main_code))
+            .into_declaration()
+            .or_statement();
 
-            let main_fn = ecma::declare::gen_func(ecma::ident("main"))
-                .body(ecma::block(main_code))
-                .into_declaration()
-                .or_statement();
-
-            match body {
-                Some(body) => {
-                    body.children.push(Child::Script(ecma::Program {
-                        body: vec![main_fn],
-                    }));
-                    body.children.push(Child::Script(machination));
-                }
-                None => todo!(),
-            }
-        }
+        // Functions that main never ends up calling would only be dead weight on the page
+        let reachable = hugs::call_graph::reachable(hir, &hugs::generate_call_graph(hir));
+        let body_code = hir
+            .module
+            .iter()
+            .filter(|(id, _)| reachable.contains(id))
+            .filter_map(|(_, item)| match item {
+                ModuleItem::Function(function) if function.name == "main" => Some(main_fn.clone()),
+                ModuleItem::Function(function) => Some(self.function_to_js(hir, function)),
+                // Operations are performed by yielding their tag, they don't exist at runtime
+                ModuleItem::Effect(_) => None,
+            })
+            .collect();
 
-        let mut buf = Vec::new();
-        let mut writer = HtmlWriter::new(&mut buf);
-        writer.write_element(&element).unwrap();
-        String::from_utf8(buf).unwrap()
+        Some([ecma::Program { body: body_code }, machination])
     }
 
     // TODO: This will eventually need to be rewriting in effectful itself and be completed
     fn html_std(call: &FunctionCallExpr) -> (&'static str, &[Expr]) {
-        match &call.name[..] {
-            "Html" => ("html", &call.children),
-            "Body" => ("body", &call.children),
-            "Paragraph" => ("p", &call.args[..1]),
+        match call.name {
+            Id::HTML => ("html", &call.children),
+            Id::BODY => ("body", &call.children),
+            Id::PARAGRAPH => ("p", &call.args),
             _ => todo!(),
         }
     }
 
     // TODO: This will eventually need to be rewriting in effectful itself
-    fn eff_std(call: &FunctionCallExpr) -> Option<(&'static str, &[Expr])> {
-        match &call.name[..] {
-            "log" => Some(("Console", &call.args)),
+    fn eff_std(id: Id) -> Option<&'static str> {
+        match id {
+            Id::LOG => Some(machination::gen_fns::CONSOLE_LOG),
+            Id::GET => Some(machination::gen_fns::FETCH_GET),
+            Id::PROMPT => Some(machination::gen_fns::DIALOG_PROMPT),
             _ => None,
         }
     }
 
-    #[allow(clippy::only_used_in_recursion)]
-    fn expr_to_html(&self, expr: &Expr) -> Child {
-        match expr {
-            Expr::StringLiteral(string_literal) => Child::Text(string_literal.clone()),
-            Expr::FunctionCall(call) => {
-                let (tag, children) = Self::html_std(call);
-                let children = children
-                    .iter()
-                    .map(|expr| self.expr_to_html(expr))
-                    .collect::<Vec<Child>>();
+    fn function_to_js(&self, hir: &Hir, function: &Function) -> ecma::StatementOrDeclaration {
+        let mut locals = Locals::default();
+        let params = function
+            .inputs
+            .iter()
+            .map(|param| ecma::ident(&locals.bind(hir, param.name)).into_pattern())
+            .collect();
+
+        let mut body = function
+            .body
+            .statements
+            .iter()
+            .map(|statement| self.stmt_to_js(hir, &mut locals, statement))
+            .collect::<Vec<_>>();
+
+        if let Some(ret) = &function.body.return_expression {
+            body.push(
+                ecma::return_statement(self.expr_to_js(hir, &locals, ret))
+                    .into_statement()
+                    .or_declaration(),
+            );
+        }
+
+        ecma::declare::gen_func(ecma::ident(&function_js_name(&function.name)))
+            .params(params)
+            .body(ecma::block(body))
+            .into_declaration()
+            .or_statement()
+    }
+
+    fn stmt_to_js(
+        &self,
+        hir: &Hir,
+        locals: &mut Locals,
+        statement: &Statement,
+    ) -> ecma::StatementOrDeclaration {
+        match statement {
+            Statement::Let(let_statement) => {
+                // The value is generated first as it can still refer to a variable this shadows
+                let value = self.expr_to_js(hir, locals, &let_statement.value);
+
+                ecma::declare::constant()
+                    .id(ecma::ident(&locals.bind(hir, let_statement.name)).into_pattern())
+                    .init(value)
+                    .into_declaration()
+                    .or_statement()
+            }
+            // A block on its own doesn't need to be wrapped in a function as nothing uses what it
+            // returns
+            Statement::ExprStatement(Expr::Block(block)) => {
+                let (mut body, ret) = self.block_parts_to_js(hir, locals, block);
+                body.extend(ret.map(|ret| ret.into_statement().or_declaration()));
+
+                ecma::Statement::Block(ecma::block(body)).or_declaration()
+            }
+            Statement::ExprStatement(Expr::If(if_expr)) => self
+                .if_to_js(hir, locals, if_expr, discard_value)
+                .into_statement()
+                .or_declaration(),
+            Statement::ExprStatement(Expr::Match(match_expr)) => ecma::Statement::Block(
+                ecma::block(self.match_to_js(hir, locals, match_expr, discard_value)),
+            )
+            .or_declaration(),
+            Statement::ExprStatement(expr) => self
+                .expr_to_js(hir, locals, expr)
+                .into_statement()
+                .or_declaration(),
+        }
+    }
+
+    /// The statements of a block and its return expression, with the block's variables scoped to it
+    fn block_parts_to_js(
+        &self,
+        hir: &Hir,
+        locals: &Locals,
+        block: &BlockExpr,
+    ) -> (Vec<ecma::StatementOrDeclaration>, Option<ecma::Expression>) {
+        let mut locals = locals.clone();
+        let statements = block
+            .statements
+            .iter()
+            .map(|statement| self.stmt_to_js(hir, &mut locals, statement))
+            .collect();
+        let ret = block
+            .return_expression
+            .as_ref()
+            .map(|ret| self.expr_to_js(hir, &locals, ret));
+
+        (statements, ret)
+    }
+
+    fn block_to_js(&self, hir: &Hir, locals: &Locals, block: &BlockExpr) -> ecma::Expression {
+        let (mut body, ret) = self.block_parts_to_js(hir, locals, block);
+
+        match ret {
+            Some(ret) if body.is_empty() => return ret,
+            Some(ret) => body.push(
+                ecma::return_statement(ret)
+                    .into_statement()
+                    .or_declaration(),
+            ),
+            None => {}
+        }
+
+        immediately_called(body)
+    }
+
+    /// Generates an `if` statement where `ret` decides what's done with the value of each branch
+    fn if_to_js(
+        &self,
+        hir: &Hir,
+        locals: &Locals,
+        if_expr: &IfExpr,
+        ret: fn(ecma::Expression) -> ecma::StatementOrDeclaration,
+    ) -> ecma::IfStatement {
+        let branch_to_js =
+            |block: &BlockExpr| ecma::block(self.branch_to_js(hir, locals, block, ret));
+
+        let if_statement = ecma::if_statement(self.expr_to_js(hir, locals, &if_expr.condition))
+            .body(branch_to_js(&if_expr.then_branch));
+
+        match &if_expr.else_branch {
+            None => if_statement,
+            Some(Expr::Block(block)) => if_statement.alternate(branch_to_js(block)),
+            Some(Expr::If(else_if)) => if_statement.alternate(ecma::block(vec![self
+                .if_to_js(hir, locals, else_if, ret)
+                .into_statement()
+                .or_declaration()])),
+            Some(_) => unreachable!("the parser only allows blocks and ifs after else"),
+        }
+    }
+
+    /// The statements of a block whose value is handed to `ret`, which is how the branches of an
+    /// `if` or the arms of a `match` are generated without a function of their own
+    fn branch_to_js(
+        &self,
+        hir: &Hir,
+        locals: &Locals,
+        block: &BlockExpr,
+        ret: fn(ecma::Expression) -> ecma::StatementOrDeclaration,
+    ) -> Vec<ecma::StatementOrDeclaration> {
+        let (mut body, value) = self.block_parts_to_js(hir, locals, block);
+        body.extend(value.map(ret));
+        body
+    }
 
-                Child::Element(Element {
-                    name: tag.to_owned(),
-                    children,
+    /// Stores the value being matched on then tests it against the pattern of each arm in turn,
+    /// `ret` decides what's done with the value of the arm that matched
+    fn match_to_js(
+        &self,
+        hir: &Hir,
+        locals: &Locals,
+        match_expr: &MatchExpr,
+        ret: fn(ecma::Expression) -> ecma::StatementOrDeclaration,
+    ) -> Vec<ecma::StatementOrDeclaration> {
+        let mut locals = locals.clone();
+        let value = self.expr_to_js(hir, &locals, &match_expr.scrutinee);
+        let scrutinee = locals.declare("$match");
+        let scrutinee_declaration = ecma::declare::constant()
+            .id(ecma::ident(&scrutinee).into_pattern())
+            .init(value)
+            .into_declaration()
+            .or_statement();
+
+        // Built from the last arm up, as each arm only runs when the ones before it didn't match
+        let mut otherwise = Vec::new();
+        for arm in match_expr.arms.iter().rev() {
+            let mut arm_locals = locals.clone();
+            let mut body = Vec::new();
+
+            let mut tests = Vec::new();
+            let mut bindings = Vec::new();
+            pattern_to_js(
+                hir,
+                &arm.pattern,
+                ecma::ident(&scrutinee).into_expression(),
+                &mut tests,
+                &mut bindings,
+            );
+            for (name, value) in bindings {
+                body.push(
+                    ecma::declare::constant()
+                        .id(ecma::ident(&arm_locals.bind(hir, name)).into_pattern())
+                        .init(value)
+                        .into_declaration()
+                        .or_statement(),
+                );
+            }
+            match &arm.body {
+                Expr::Block(block) => body.extend(self.branch_to_js(hir, &arm_locals, block, ret)),
+                expr => body.push(ret(self.expr_to_js(hir, &arm_locals, expr))),
+            }
+
+            otherwise = match tests.into_iter().reduce(ecma::Expression::and) {
+                None => body,
+                Some(test) => {
+                    let if_statement = ecma::if_statement(test).body(ecma::block(body));
+                    let if_statement = match otherwise.is_empty() {
+                        true => if_statement,
+                        false => if_statement.alternate(ecma::block(otherwise)),
+                    };
+
+                    vec![if_statement.into_statement().or_declaration()]
+                }
+            };
+        }
+
+        std::iter::once(scrutinee_declaration)
+            .chain(otherwise)
+            .collect()
+    }
+
+    /// Runs the handled block as a generator of its own and steps through it, every operation it
+    /// yields either goes to the clause handling it or is passed on to whatever is running this
+    ///
+    /// The clauses are given a `resume` that continues the handled block with the value it's called
+    /// with, which keeps stepping through it the same way until it finishes
+    fn handle_to_js(
+        &self,
+        hir: &Hir,
+        locals: &Locals,
+        handle: &HandleExpr,
+    ) -> Vec<ecma::StatementOrDeclaration> {
+        let mut locals = locals.clone();
+        let (mut body, ret) = self.block_parts_to_js(hir, &locals, &handle.body);
+        body.extend(ret.map(return_value));
+        let handled = locals.declare("$handled");
+        let resume = locals.declare("$resume");
+
+        let handled_declaration = ecma::declare::constant()
+            .id(ecma::ident(&handled).into_pattern())
+            .init(
+                ecma::gen_func_expr(ecma::block(body))
+                    .into_expression()
+                    .call(Vec::new())
+                    .into_expression(),
+            )
+            .into_declaration()
+            .or_statement();
+
+        let mut steps = vec![
+            ecma::declare::constant()
+                .id(ecma::obj_pat(vec![
+                    ("done", Some("$done")),
+                    ("value", Some("$eff")),
+                ]))
+                .init(
+                    ecma::ident(&handled)
+                        .member_access("next")
+                        .call(vec![ecma::ident("$value").into_expression()])
+                        .into_expression(),
+                )
+                .into_declaration()
+                .or_statement(),
+            ecma::if_statement(ecma::ident("$done").into_expression())
+                .body(ecma::block(vec![return_value(
+                    ecma::ident("$eff").into_expression(),
+                )]))
+                .into_statement()
+                .or_declaration(),
+        ];
+
+        for clause in &handle.clauses {
+            let mut clause_locals = locals.clone();
+            let mut body = clause
+                .params
+                .iter()
+                .enumerate()
+                .map(|(index, param)| {
+                    ecma::declare::constant()
+                        .id(ecma::ident(&clause_locals.bind(hir, param.name)).into_pattern())
+                        .init(
+                            ecma::ident("$eff")
+                                .member_access("args")
+                                .dyn_member_access(ecma::int(index as i64).into_expression())
+                                .into_expression(),
+                        )
+                        .into_declaration()
+                        .or_statement()
                 })
+                .collect::<Vec<_>>();
+            body.push(
+                ecma::declare::constant()
+                    .id(ecma::ident(&clause_locals.bind(hir, clause.resume)).into_pattern())
+                    .init(ecma::ident(&resume).into_expression())
+                    .into_declaration()
+                    .or_statement(),
+            );
+            body.push(return_value(self.expr_to_js(
+                hir,
+                &clause_locals,
+                &clause.body,
+            )));
+
+            let tag = operation_tag(hir, clause.operation)
+                .expect("type checking ensures clauses handle operations");
+            steps.push(
+                ecma::if_statement(
+                    ecma::ident("$eff")
+                        .member_access("ty")
+                        .into_expression()
+                        .strict_eq(ecma::string(&tag).into_expression()),
+                )
+                .body(ecma::block(body))
+                .into_statement()
+                .or_declaration(),
+            );
+        }
+
+        // Operations of other effects are performed by whatever is around the handler
+        steps.push(return_value(
+            ecma::yield_delegate(
+                ecma::ident(&resume)
+                    .call(vec![
+                        ecma::yield_(ecma::ident("$eff").into_expression()).into_expression()
+                    ])
+                    .into_expression(),
+            )
+            .into_expression(),
+        ));
+
+        let resume_declaration = ecma::declare::gen_func(ecma::ident(&resume))
+            .params(vec![ecma::ident("$value").into_pattern()])
+            .body(ecma::block(steps))
+            .into_declaration()
+            .or_statement();
+
+        vec![
+            handled_declaration,
+            resume_declaration,
+            return_value(
+                ecma::yield_delegate(ecma::ident(&resume).call(Vec::new()).into_expression())
+                    .into_expression(),
+            ),
+        ]
+    }
+
+    fn call_to_js(&self, hir: &Hir, locals: &Locals, call: &FunctionCallExpr) -> ecma::Expression {
+        let to_js = |args: &[Expr]| {
+            args.iter()
+                .map(|arg| self.expr_to_js(hir, locals, arg))
+                .collect::<Vec<_>>()
+        };
+
+        // The only locals that can be called are the `resume`s of handlers, which are generators
+        if let Some(js_name) = locals.get(call.name) {
+            return ecma::yield_delegate(
+                ecma::ident(js_name)
+                    .call(to_js(&call.args))
+                    .into_expression(),
+            )
+            .into_expression();
+        }
+
+        match callee(hir, call.name) {
+            // Every function is a generator so that it's able to perform effects
+            Callee::Function(function) => {
+                return ecma::yield_delegate(
+                    ecma::ident(&function_js_name(&function.name))
+                        .call(to_js(&call.args))
+                        .into_expression(),
+                )
+                .into_expression()
+            }
+            Callee::Operation(tag) => return machination::gen_fns::effect(&tag, to_js(&call.args)),
+            Callee::Constructor(tag) => {
+                return ecma::obj(vec![
+                    ("tag", Some(ecma::string(&tag).into_expression())),
+                    (
+                        "fields",
+                        Some(ecma::array(to_js(&call.args)).into_expression()),
+                    ),
+                ])
+                .into_expression()
             }
+            Callee::Builtin => {}
         }
+
+        // At runtime Html is represented as a plain string of markup
+        let (tag, children) = Self::html_std(call);
+        let mut parts = vec![ecma::string(&format!("<{tag}>")).into_expression()];
+        parts.extend(to_js(children));
+        parts.push(ecma::string(&format!("</{tag}>")).into_expression());
+
+        ecma::array(parts)
+            .into_expression()
+            .member_access("join")
+            .call(vec![ecma::string("").into_expression()])
+            .into_expression()
     }
 
-    fn stmt_to_js(&self, statement: &Statement) -> ecma::Statement {
-        match statement {
-            Statement::ExprStatement(expr) => match expr {
-                Expr::StringLiteral(_) => todo!(),
-                Expr::FunctionCall(fn_call) => {
-                    if let Some((eff, extra)) = Self::eff_std(fn_call) {
-                        machination::gen_fns::effect(eff, extra)
-                    } else {
-                        todo!()
-                    }
+    fn expr_to_js(&self, hir: &Hir, locals: &Locals, expr: &Expr) -> ecma::Expression {
+        match expr {
+            Expr::StringLiteral(string_literal) => {
+                ecma::string(&string_literal.value).into_expression()
+            }
+            Expr::IntLiteral(int_literal) => ecma::int(int_literal.value).into_expression(),
+            Expr::FloatLiteral(float_literal) => ecma::float(float_literal.value).into_expression(),
+            Expr::BoolLiteral(bool_literal) => ecma::boolean(bool_literal.value).into_expression(),
+            Expr::Error(_) => unreachable!("code is never generated for ASTs with errors"),
+            Expr::Block(block) => self.block_to_js(hir, locals, block),
+            // Like blocks, an `if` used as a value is wrapped in an immediately called generator
+            // function so each of its branches can return their value
+            Expr::If(if_expr) => immediately_called(vec![self
+                .if_to_js(hir, locals, if_expr, return_value)
+                .into_statement()
+                .or_declaration()]),
+            Expr::Match(match_expr) => {
+                immediately_called(self.match_to_js(hir, locals, match_expr, return_value))
+            }
+            Expr::Handle(handle) => immediately_called(self.handle_to_js(hir, locals, handle)),
+            Expr::Unit(_) => ecma::ident("undefined").into_expression(),
+            Expr::Variable(variable) => ecma::ident(
+                locals
+                    .get(variable.name)
+                    .expect("variables to be declared before they're used"),
+            )
+            .into_expression(),
+            Expr::FunctionCall(call) => self.call_to_js(hir, locals, call),
+        }
+    }
+}
+
+/// What a call refers to
+enum Callee<'a> {
+    Function(&'a Function),
+    /// An operation, by the tag it's performed with
+    Operation(String),
+    /// A constructor, by the tag the values it makes are told apart with
+    Constructor(String),
+    /// Something from std that isn't an operation
+    Builtin,
+}
+
+fn callee(hir: &Hir, id: Id) -> Callee<'_> {
+    if let Some(ModuleItem::Function(function)) = hir.module.get(&id) {
+        return Callee::Function(function);
+    }
+
+    if let Some(tag) = constructor_tag(hir, id) {
+        return Callee::Constructor(tag);
+    }
+
+    match operation_tag(hir, id) {
+        Some(tag) => Callee::Operation(tag),
+        None => Callee::Builtin,
+    }
+}
+
+/// Adds what a value has to be for a pattern to match it to `tests`, and what the names the pattern
+/// binds are bound to to `bindings`
+fn pattern_to_js(
+    hir: &Hir,
+    pattern: &Pattern,
+    value: ecma::Expression,
+    tests: &mut Vec<ecma::Expression>,
+    bindings: &mut Vec<(Id, ecma::Expression)>,
+) {
+    match pattern {
+        Pattern::Wildcard(_) => {}
+        Pattern::Binding(name) => bindings.push((name.name, value)),
+        Pattern::StringLiteral(string_literal) => {
+            tests.push(value.strict_eq(ecma::string(&string_literal.value).into_expression()))
+        }
+        Pattern::IntLiteral(int_literal) => {
+            tests.push(value.strict_eq(ecma::int(int_literal.value).into_expression()))
+        }
+        Pattern::BoolLiteral(bool_literal) => {
+            tests.push(value.strict_eq(ecma::boolean(bool_literal.value).into_expression()))
+        }
+        // The tag is checked before the fields so that they're only looked at when they exist
+        Pattern::Constructor(constructor) => {
+            let tag = constructor_tag(hir, constructor.name)
+                .expect("type checking rejects constructors that don't exist");
+            tests.push(
+                value
+                    .clone()
+                    .member_access("tag")
+                    .into_expression()
+                    .strict_eq(ecma::string(&tag).into_expression()),
+            );
+            for (index, field) in constructor.fields.iter().enumerate() {
+                let field_value = value
+                    .clone()
+                    .member_access("fields")
+                    .dyn_member_access(ecma::int(index as i64).into_expression())
+                    .into_expression();
+                pattern_to_js(hir, field, field_value, tests, bindings);
+            }
+        }
+        Pattern::Error(_) => unreachable!("code is never generated for ASTs with errors"),
+    }
+}
+
+/// Whether working out an expression performs any effects other than the ones handled inside of
+/// it, `handled` being the effects handled around it along with the `resume`s that continue
+/// handled blocks which don't perform anything that isn't handled
+fn performs(hir: &Hir, expr: &Expr, handled: &[Id]) -> bool {
+    let block_performs = |block: &BlockExpr, handled: &[Id]| {
+        block.statements.iter().any(|statement| match statement {
+            Statement::Let(let_statement) => performs(hir, &let_statement.value, handled),
+            Statement::ExprStatement(expr) => performs(hir, expr, handled),
+        }) || block
+            .return_expression
+            .as_ref()
+            .is_some_and(|expr| performs(hir, expr, handled))
+    };
+
+    match expr {
+        Expr::StringLiteral(_)
+        | Expr::IntLiteral(_)
+        | Expr::FloatLiteral(_)
+        | Expr::BoolLiteral(_)
+        | Expr::Variable(_)
+        | Expr::Unit(_)
+        | Expr::Error(_) => false,
+        Expr::FunctionCall(call) => {
+            let performs_itself = match callee(hir, call.name) {
+                Callee::Function(function) => function
+                    .output
+                    .effects
+                    .iter()
+                    .any(|(effect, _)| !handled.contains(&effect)),
+                Callee::Operation(_) => match hir.operation(call.name) {
+                    Some((effect, _)) => !handled.contains(&effect),
+                    None => !matches!(
+                        hir::std_kind(call.name),
+                        Some(hir::Kind::Operation(effect)) if handled.contains(&effect)
+                    ),
+                },
+                Callee::Constructor(_) => false,
+                // Other than the elements from std, the only things that can be called are the
+                // `resume`s of handlers
+                Callee::Builtin => {
+                    !matches!(call.name, Id::HTML | Id::BODY | Id::PARAGRAPH)
+                        && !handled.contains(&call.name)
                 }
-            },
+            };
+            performs_itself
+                || call
+                    .args
+                    .iter()
+                    .chain(&call.children)
+                    .any(|expr| performs(hir, expr, handled))
+        }
+        Expr::Block(block) => block_performs(block, handled),
+        Expr::If(if_expr) => {
+            performs(hir, &if_expr.condition, handled)
+                || block_performs(&if_expr.then_branch, handled)
+                || if_expr
+                    .else_branch
+                    .as_ref()
+                    .is_some_and(|expr| performs(hir, expr, handled))
+        }
+        Expr::Match(match_expr) => {
+            performs(hir, &match_expr.scrutinee, handled)
+                || match_expr
+                    .arms
+                    .iter()
+                    .any(|arm| performs(hir, &arm.body, handled))
+        }
+        Expr::Handle(handle) => {
+            if block_performs(&handle.body, &[handled, &[handle.effect]].concat()) {
+                return true;
+            }
+            // Clauses run outside of the handler, so what they perform isn't handled by it
+            handle
+                .clauses
+                .iter()
+                .any(|clause| performs(hir, &clause.body, &[handled, &[clause.resume]].concat()))
         }
     }
 }
 
+/// The tag an operation is performed with, which is the path to it so that no two operations can
+/// share the same tag
+fn operation_tag(hir: &Hir, id: Id) -> Option<String> {
+    if let Some((effect, _)) = hir.operation(id) {
+        return Some(format!("{}::{}", name(hir, effect), name(hir, id)));
+    }
+
+    Generator::eff_std(id).map(str::to_string)
+}
+
+/// The tag the values a constructor makes are told apart with at runtime, which is the path to it
+/// from its type
+fn constructor_tag(hir: &Hir, id: Id) -> Option<String> {
+    match hir::std_kind(id) {
+        Some(hir::Kind::Constructor(ty)) => Some(format!("{}::{}", name(hir, ty), name(hir, id))),
+        _ => None,
+    }
+}
+
+/// The name an id was declared with
+fn name(hir: &Hir, id: Id) -> &str {
+    hir.id_map.get(&id).expect("every id to have a name")
+}
+
+/// The reserved words of JS, which it doesn't allow as variable names, and the variables the runtime
+/// declares
+const RESERVED_NAMES: [&str; 50] = [
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+    "main_call",
+    "$value",
+];
+
+/// Globals the generated JS and the runtime read, which a variable of the same name would shadow
+const GLOBALS: [&str; 6] = ["undefined", "NaN", "Infinity", "console", "fetch", "prompt"];
+
+/// The JS name of a variable named `name` in effectful, names that JS wouldn't accept or that would
+/// clash with the runtime get a `$` after them which effectful names can never have
+fn js_name(name: &str) -> String {
+    if RESERVED_NAMES.contains(&name) || GLOBALS.contains(&name) {
+        format!("{name}$")
+    } else {
+        name.to_string()
+    }
+}
+
+/// The JS name of a function, which has a prefix that no variable can have so that variables never
+/// shadow the functions they share a name with
+fn function_js_name(name: &str) -> String {
+    format!("fn${name}")
+}
+
+/// Wraps statements in a generator function which is immediately called, that keeps their
+/// variables scoped to them and lets them perform effects while still being used as a value
+fn immediately_called(body: Vec<ecma::StatementOrDeclaration>) -> ecma::Expression {
+    ecma::yield_delegate(
+        ecma::gen_func_expr(ecma::block(body))
+            .into_expression()
+            .call(Vec::new())
+            .into_expression(),
+    )
+    .into_expression()
+}
+
+fn return_value(value: ecma::Expression) -> ecma::StatementOrDeclaration {
+    ecma::return_statement(value)
+        .into_statement()
+        .or_declaration()
+}
+
+fn discard_value(value: ecma::Expression) -> ecma::StatementOrDeclaration {
+    value.into_statement().or_declaration()
+}
+
 impl Default for Generator {
     fn default() -> Self {
         Self::new()
@@ -1,87 +1,136 @@
+use hugs::hir::Id;
+
 use crate::ecma::{
     array, block, boolean, break_statement, declare, ident, if_statement, int, obj, obj_pat,
-    string, while_statement, yield_, Program, Statement,
+    string, while_statement, yield_, Expression, Program, StatementOrDeclaration,
 };
 
-pub fn machination() -> Program {
+// Operations are yielded along with a tag that tells the runtime which operation it's handling, the
+// tag is the path to the operation so that two operations can never share the same tag
+pub const CONSOLE_LOG: &str = "std::console::Console::log";
+pub const FETCH_GET: &str = "std::fetch::Fetch::get";
+pub const DIALOG_PROMPT: &str = "std::dialog::Dialog::prompt";
+
+/// What the runtime does when it's asked to perform an effect from std, what it gets back is what
+/// `main` is resumed with unless that isn't what the operation outputs, then it's resumed with `()`
+fn handler(effect: Id) -> StatementOrDeclaration {
+    let first_arg = ident("eff")
+        .member_access("args")
+        .dyn_member_access(int(0).into_expression())
+        .into_expression();
+
+    let (tag, call, resumes_with_result) = match effect {
+        Id::CONSOLE => (
+            CONSOLE_LOG,
+            ident("console")
+                .member_access("log")
+                .call(vec![first_arg])
+                .into_expression(),
+            true,
+        ),
+        // The request is only sent, `get` outputs `()` as the response comes back in a promise
+        // that nothing waits for
+        Id::FETCH => (
+            FETCH_GET,
+            ident("fetch").call(vec![first_arg]).into_expression(),
+            false,
+        ),
+        // Cancelling the prompt gives back `null`, which isn't a `String`
+        Id::DIALOG => (
+            DIALOG_PROMPT,
+            ident("prompt")
+                .call(vec![first_arg])
+                .into_expression()
+                .nullish_or(string("").into_expression()),
+            true,
+        ),
+        _ => unreachable!("type checking only lets `main` perform the effects from std"),
+    };
+
+    if_statement(
+        ident("eff")
+            .member_access("ty")
+            .into_expression()
+            .strict_eq(string(tag).into_expression()),
+    )
+    .body(block(match resumes_with_result {
+        true => vec![ident("$value")
+            .assign(call)
+            .into_statement()
+            .or_declaration()],
+        false => vec![
+            call.into_statement().or_declaration(),
+            ident("$value")
+                .assign(ident("undefined").into_expression())
+                .into_statement()
+                .or_declaration(),
+        ],
+    }))
+    .into_statement()
+    .or_declaration()
+}
+
+/// Runs `main` by the JS name it's given, performing every effect it yields with the handler of the
+/// effect and resuming it with the result
+pub fn machination(main: &str, effects: &[Id]) -> Program {
     Program {
         body: vec![
             declare::constant()
                 .id(ident("main_call").into_pattern())
-                .init(ident("main").call(vec![]).into_expression())
+                .init(ident(main).call(vec![]).into_expression())
+                .into_declaration()
+                .or_statement(),
+            declare::variable()
+                .id(ident("$value").into_pattern())
+                .init(ident("undefined").into_expression())
                 .into_declaration()
                 .or_statement(),
             while_statement(boolean(true).into_expression())
-                .body(block(vec![
-                    declare::constant()
-                        .id(obj_pat(vec![("done", None), ("value", Some("eff"))]))
-                        .init(
-                            ident("main_call")
-                                .member_access("next")
-                                .call(vec![])
-                                .into_expression(),
-                        )
-                        .into_declaration()
-                        .or_statement(),
-                    if_statement(ident("done").into_expression())
-                        .body(block(vec![break_statement()
+                .body(block(
+                    vec![
+                        declare::constant()
+                            .id(obj_pat(vec![("done", None), ("value", Some("eff"))]))
+                            .init(
+                                ident("main_call")
+                                    .member_access("next")
+                                    .call(vec![ident("$value").into_expression()])
+                                    .into_expression(),
+                            )
+                            .into_declaration()
+                            .or_statement(),
+                        if_statement(ident("done").into_expression())
+                            .body(block(vec![break_statement()
+                                .into_statement()
+                                .or_declaration()]))
                             .into_statement()
-                            .or_declaration()]))
-                        .into_statement()
-                        .or_declaration(),
-                    if_statement(
-                        ident("eff")
-                            .member_access("ty")
-                            .into_expression()
-                            .strict_eq(string("__CONSOLE__").into_expression()),
-                    )
-                    .body(block(vec![ident("console")
-                        .member_access("log")
-                        .call(vec![ident("eff")
-                            .member_access("args")
-                            .dyn_member_access(int(0).into_expression())
-                            .into_expression()])
-                        .into_statement()
-                        .or_declaration()]))
-                    .into_statement()
-                    .or_declaration(),
-                ]))
+                            .or_declaration(),
+                    ]
+                    .into_iter()
+                    .chain(effects.iter().map(|&effect| handler(effect)))
+                    .collect(),
+                ))
                 .into_statement()
                 .or_declaration(),
         ],
     }
 }
 
-pub fn effect(eff: &str, extra: &[parser::Expr]) -> Statement {
-    match eff {
-        "Console" => yield_(
-            obj(vec![
-                ("ty", Some(string("__CONSOLE__").into_expression())),
-                (
-                    "args",
-                    Some(
-                        array(
-                            extra
-                                .iter()
-                                .map(|expr| match expr {
-                                    parser::Expr::StringLiteral(s) => string(s).into_expression(),
-                                    _ => unreachable!(),
-                                })
-                                .collect(),
-                        )
-                        .into_expression(),
-                    ),
-                ),
-            ])
-            .into_expression(),
-        )
-        .into_statement(),
-        _ => todo!(),
-    }
+/// Performs an operation by handing it to whatever is running the generator it's yielded from
+pub fn effect(tag: &str, args: Vec<Expression>) -> Expression {
+    yield_(
+        obj(vec![
+            ("ty", Some(string(tag).into_expression())),
+            ("args", Some(array(args).into_expression())),
+        ])
+        .into_expression(),
+    )
+    .into_expression()
 }
 
 #[cfg(test)]
 mod tests {
+    use hugs::hir::Id;
+
     use crate::ecma::writer::EcmaWriter;
 
     // TODO: This test should be redundant once we have a full generation test
@@ -89,12 +138,14 @@ mod tests {
     fn machination() {
         let mut output = Vec::new();
         let mut writer = EcmaWriter::new(&mut output);
-        writer.write_program(&super::machination()).unwrap();
+        writer
+            .write_program(&super::machination("main", &[Id::CONSOLE]))
+            .unwrap();
         let output = String::from_utf8(output).unwrap();
 
         assert_eq!(
             output,
-            r#"const main_call=main();while(true){const {done,value:eff,}=main_call.next();if(done){break;}if(eff.ty==="__CONSOLE__"){console.log(eff.args[0],);}}"#
+            r#"const main_call=main();let $value=undefined;while(true){const {done,value:eff,}=main_call.next($value,);if(done){break;}if(eff.ty==="std::console::Console::log"){$value=console.log(eff.args[0],);}}"#
         );
     }
 }
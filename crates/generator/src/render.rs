@@ -0,0 +1,602 @@
+//! Renders what `main` returns into static HTML by evaluating it while generating
+//!
+//! Calls are inlined by rendering what the function returns with its params bound to the args, and
+//! the value of a `let` is only evaluated once something that's rendered uses it
+//!
+//! Operations are performed to the handlers around them the same way they are at runtime, except
+//! for those of effects from std which are left for the runtime when nothing handles them
+
+use std::{
+    cell::{OnceCell, RefCell},
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+use diagnostics::{codes, Diagnostic};
+use hugs::hir::{
+    self, BlockExpr, Expr, Function, FunctionCallExpr, HandleExpr, Hir, Id, IfExpr, Kind,
+    MatchExpr, ModuleItem, Pattern, Statement,
+};
+
+use indexmap::IndexMap;
+use parser::Span;
+
+use crate::{
+    callee, ecma,
+    html::{Child, Element},
+    Callee, Generator,
+};
+
+/// A value known while generating, which is what function calls get inlined into when they are
+/// rendered into static HTML
+#[derive(Clone)]
+pub enum Value {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Html(Element),
+    /// A value made with a constructor, along with what it holds
+    Constructed(Id, Vec<Value>),
+    /// What an `if` without an `else` evaluates to when its condition is false
+    Nothing,
+    /// What a call that can't be made while rendering returns, which is only known once the page
+    /// is running
+    Runtime(Runtime),
+}
+
+impl Value {
+    fn into_child(self) -> Option<Child> {
+        let child = match self {
+            Value::String(string) => Child::Text(string),
+            // Ints too large for a JS number are BigInts at runtime, which are shown without their
+            // `n` so every int is shown the same way whichever one it is
+            Value::Int(int) => Child::Text(int.to_string()),
+            Value::Float(float) => Child::Text(ecma::number_to_string(float)),
+            Value::Bool(bool) => Child::Text(bool.to_string()),
+            Value::Html(element) => Child::Element(element),
+            Value::Constructed(..) | Value::Nothing | Value::Runtime(_) => return None,
+        };
+
+        Some(child)
+    }
+}
+
+/// Where a value that's only known at runtime came from
+#[derive(Clone, Copy)]
+pub struct Runtime {
+    callee: Id,
+    span: Span,
+}
+
+/// What a variable is bound to while rendering
+#[derive(Clone)]
+enum Binding<'h> {
+    Value(Value),
+    Lazy(Rc<Lazy<'h>>),
+    /// The `resume` of a handler clause
+    Resume(Rc<Resumption<'h>>),
+}
+
+/// The value of a `let`, which is evaluated the first time it's used and kept from then on
+struct Lazy<'h> {
+    expr: &'h Expr,
+    env: Env<'h>,
+    value: OnceCell<Value>,
+}
+
+/// Where a handled block performed the operation a clause is handling, which is where it continues
+/// from when the clause resumes it
+struct Resumption<'h> {
+    handle: &'h HandleExpr,
+    env: Env<'h>,
+    /// What the handled block was resumed with before it performed the operation
+    resumed: Vec<Value>,
+}
+
+type Env<'h> = HashMap<Id, Binding<'h>>;
+
+/// A `handle` whose block is being rendered
+///
+/// There's no way to pause rendering where an operation is performed and come back to it later, so
+/// resuming renders the handled block again from the start, with each operation it performs
+/// evaluating to what it was resumed with the last time until it gets to the one being resumed
+struct Handler {
+    effect: Id,
+    /// What every operation performed inside of the handled block was resumed with, in the order
+    /// they were performed, other than those handled by handlers inside of it
+    resumed: Vec<Value>,
+    /// How many of those were performed again since the handled block was started
+    replayed: usize,
+}
+
+/// An operation that was performed to the handler at `depth` and hasn't been resumed yet, which
+/// stops everything until the handler is reached
+struct Performed {
+    depth: usize,
+    operation: Id,
+    args: Vec<Value>,
+}
+
+pub struct Renderer<'h> {
+    hir: &'h Hir,
+    /// Functions whose calls are never inlined, what they return is only known at runtime
+    ///
+    /// Functions that call themselves could be inlined forever, and ones that perform effects from
+    /// std return values that depend on what those effects output
+    not_inlined: HashSet<Id>,
+    /// The handlers around what's being rendered, from the outermost one in
+    handlers: RefCell<Vec<Handler>>,
+    /// Uses of values that are only known at runtime, each reported once where it came from
+    diagnostics: RefCell<IndexMap<Span, Diagnostic>>,
+}
+
+impl<'h> Renderer<'h> {
+    pub fn new(hir: &'h Hir) -> Self {
+        let graph = hugs::generate_call_graph(hir);
+        let mut not_inlined = hugs::call_graph::recursive(hir, &graph)
+            .concat()
+            .into_iter()
+            .collect::<HashSet<_>>();
+        not_inlined.extend(hir.module.iter().filter_map(|(id, item)| match item {
+            ModuleItem::Function(function) if performs_std(hir, function) => Some(*id),
+            _ => None,
+        }));
+
+        Self {
+            hir,
+            not_inlined,
+            handlers: RefCell::new(Vec::new()),
+            diagnostics: RefCell::new(IndexMap::new()),
+        }
+    }
+
+    /// Renders what a function with no params returns, failing when it depends on values that are
+    /// only known at runtime
+    pub fn render(self, function: &'h Function) -> Result<Value, Vec<Diagnostic>> {
+        let value = match self.block(&Env::new(), &function.body) {
+            Ok(Value::Runtime(runtime)) => {
+                self.needed(
+                    runtime,
+                    block_value_span(&function.body),
+                    "rendered as the page here",
+                );
+                Value::Nothing
+            }
+            Ok(value) => value,
+            Err(_) => unreachable!("effect checking ensures every operation is handled"),
+        };
+
+        let mut diagnostics = self.diagnostics.into_inner();
+        // Lets are evaluated once they're used, which isn't the order they're written in
+        diagnostics.sort_by(|a, _, b, _| a.start.cmp(&b.start));
+        if diagnostics.is_empty() {
+            Ok(value)
+        } else {
+            Err(diagnostics.into_values().collect())
+        }
+    }
+
+    /// Reports a value that's only known at runtime being used where it's needed while rendering
+    fn needed(&self, runtime: Runtime, span: Span, label: &str) {
+        let name = self
+            .hir
+            .id_map
+            .get(&runtime.callee)
+            .map_or("{unknown}", |name| name);
+        let (performed, note) = match self.hir.module.get(&runtime.callee) {
+            Some(ModuleItem::Function(function)) if performs_std(self.hir, function) => (
+                "it isn't called while rendering",
+                "functions that perform effects from std are only called once the page is running",
+            ),
+            Some(_) => (
+                "it isn't called while rendering",
+                "functions that call themselves aren't called while rendering, as they might never \
+                 return",
+            ),
+            None => (
+                "it's performed once the page is running",
+                "operations from std are only performed once the page is running, unless they're \
+                 handled",
+            ),
+        };
+
+        self.diagnostics
+            .borrow_mut()
+            .entry(runtime.span)
+            .or_insert_with(|| {
+                let diagnostic = Diagnostic::error(
+                    codes::RUNTIME_VALUE,
+                    runtime.span,
+                    format!("what `{name}` returns is only known once the page is running"),
+                )
+                .with_label(performed)
+                .with_note(note);
+                match span == runtime.span {
+                    true => diagnostic,
+                    false => diagnostic.with_secondary(span, label),
+                }
+            });
+    }
+
+    /// Whether evaluating an expression could perform an operation or resume a handled block,
+    /// which has to happen in the order it's written in so it can't be put off like other lets
+    fn performs(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::StringLiteral(_)
+            | Expr::IntLiteral(_)
+            | Expr::FloatLiteral(_)
+            | Expr::BoolLiteral(_)
+            | Expr::Variable(_)
+            | Expr::Unit(_)
+            | Expr::Error(_) => false,
+            Expr::FunctionCall(call) => {
+                let performs = match callee(self.hir, call.name) {
+                    Callee::Function(function) => {
+                        !self.not_inlined.contains(&call.name)
+                            && !function.output.effects.is_empty()
+                    }
+                    Callee::Operation(_) => true,
+                    Callee::Constructor(_) => false,
+                    // Other than the elements from std, the only things that can be called are the
+                    // `resume`s of handlers
+                    Callee::Builtin => !matches!(call.name, Id::HTML | Id::BODY | Id::PARAGRAPH),
+                };
+                performs
+                    || call
+                        .args
+                        .iter()
+                        .chain(&call.children)
+                        .any(|expr| self.performs(expr))
+            }
+            Expr::Block(block) => self.block_performs(block),
+            Expr::If(if_expr) => {
+                self.performs(&if_expr.condition)
+                    || self.block_performs(&if_expr.then_branch)
+                    || (if_expr.else_branch.as_ref()).is_some_and(|expr| self.performs(expr))
+            }
+            Expr::Match(match_expr) => {
+                self.performs(&match_expr.scrutinee)
+                    || match_expr.arms.iter().any(|arm| self.performs(&arm.body))
+            }
+            Expr::Handle(handle) => {
+                self.block_performs(&handle.body)
+                    || handle
+                        .clauses
+                        .iter()
+                        .any(|clause| self.performs(&clause.body))
+            }
+        }
+    }
+
+    fn block_performs(&self, block: &BlockExpr) -> bool {
+        block.statements.iter().any(|statement| match statement {
+            Statement::Let(let_statement) => self.performs(&let_statement.value),
+            Statement::ExprStatement(expr) => self.performs(expr),
+        }) || (block.return_expression.as_ref()).is_some_and(|expr| self.performs(expr))
+    }
+
+    fn force(&self, binding: &Binding<'h>) -> Result<Value, Performed> {
+        match binding {
+            Binding::Value(value) => Ok(value.clone()),
+            Binding::Lazy(lazy) => {
+                if let Some(value) = lazy.value.get() {
+                    return Ok(value.clone());
+                }
+                let value = self.expr(&lazy.env, lazy.expr)?;
+                Ok(lazy.value.get_or_init(|| value).clone())
+            }
+            Binding::Resume(_) => unreachable!("type checking ensures `resume` is only called"),
+        }
+    }
+
+    /// Renders the return expression of a block, with the variables bound by its statements in
+    /// scope, blocks without one are `()` and render as nothing
+    ///
+    /// Statements are skipped unless they perform operations, which could change what's rendered
+    /// when they're handled
+    fn block(&self, env: &Env<'h>, block: &'h BlockExpr) -> Result<Value, Performed> {
+        let mut env = env.clone();
+        for statement in &block.statements {
+            match statement {
+                Statement::Let(let_statement) if self.performs(&let_statement.value) => {
+                    let value = self.expr(&env, &let_statement.value)?;
+                    env.insert(let_statement.name, Binding::Value(value));
+                }
+                Statement::Let(let_statement) => {
+                    let lazy = Lazy {
+                        expr: &let_statement.value,
+                        env: env.clone(),
+                        value: OnceCell::new(),
+                    };
+                    env.insert(let_statement.name, Binding::Lazy(Rc::new(lazy)));
+                }
+                Statement::ExprStatement(expr) if self.performs(expr) => {
+                    self.expr(&env, expr)?;
+                }
+                Statement::ExprStatement(_) => {}
+            }
+        }
+
+        match &block.return_expression {
+            Some(ret) => self.expr(&env, ret),
+            None => Ok(Value::Nothing),
+        }
+    }
+
+    /// Calls a function by rendering what it returns with its params bound to the args
+    fn inline_call(
+        &self,
+        env: &Env<'h>,
+        function: &'h Function,
+        args: &'h [Expr],
+    ) -> Result<Value, Performed> {
+        let mut params = Env::new();
+        for (param, arg) in function.inputs.iter().zip(args) {
+            params.insert(param.name, Binding::Value(self.expr(env, arg)?));
+        }
+
+        self.block(&params, &function.body)
+    }
+
+    fn call(&self, env: &Env<'h>, call: &'h FunctionCallExpr) -> Result<Value, Performed> {
+        if let Some(Binding::Resume(resumption)) = env.get(&call.name) {
+            let mut resumed = resumption.resumed.clone();
+            for arg in &call.args {
+                resumed.push(self.expr(env, arg)?);
+            }
+            return self.handle(&resumption.env, resumption.handle, resumed);
+        }
+
+        match callee(self.hir, call.name) {
+            Callee::Function(_) if self.not_inlined.contains(&call.name) => {
+                Ok(Value::Runtime(Runtime {
+                    callee: call.name,
+                    span: call.span,
+                }))
+            }
+            Callee::Function(function) => self.inline_call(env, function, &call.args),
+            Callee::Operation(_) => self.perform(env, call),
+            Callee::Constructor(_) => {
+                let mut fields = Vec::new();
+                for arg in &call.args {
+                    fields.push(self.expr(env, arg)?);
+                }
+                Ok(Value::Constructed(call.name, fields))
+            }
+            Callee::Builtin => self.builtin(env, call),
+        }
+    }
+
+    /// Performs an operation to the closest handler of its effect, operations of effects from std
+    /// that aren't handled are left to the runtime
+    fn perform(&self, env: &Env<'h>, call: &'h FunctionCallExpr) -> Result<Value, Performed> {
+        let mut args = Vec::new();
+        for arg in &call.args {
+            args.push(self.expr(env, arg)?);
+        }
+
+        let effect = match (self.hir.operation(call.name), hir::std_kind(call.name)) {
+            (Some((effect, _)), _) | (None, Some(Kind::Operation(effect))) => effect,
+            _ => unreachable!("operations belong to an effect"),
+        };
+        let mut handlers = self.handlers.borrow_mut();
+        let Some(depth) = handlers
+            .iter()
+            .rposition(|handler| handler.effect == effect)
+        else {
+            return Ok(Value::Runtime(Runtime {
+                callee: call.name,
+                span: call.span,
+            }));
+        };
+
+        // Handlers inside of the one handling this only know what it was resumed with if they're
+        // being replayed, in which case it was already performed since the outer ones started
+        let Some(known) = (depth..handlers.len())
+            .rev()
+            .find(|&known| handlers[known].replayed < handlers[known].resumed.len())
+        else {
+            return Err(Performed {
+                depth,
+                operation: call.name,
+                args,
+            });
+        };
+
+        let value = handlers[known].resumed[handlers[known].replayed].clone();
+        handlers[known].replayed += 1;
+        for handler in &mut handlers[known + 1..] {
+            handler.resumed.push(value.clone());
+            handler.replayed += 1;
+        }
+        Ok(value)
+    }
+
+    /// Renders the handled block, if it performs an operation the value of the `handle` is that of
+    /// the clause handling it instead
+    fn handle(
+        &self,
+        env: &Env<'h>,
+        handle: &'h HandleExpr,
+        resumed: Vec<Value>,
+    ) -> Result<Value, Performed> {
+        let depth = {
+            let mut handlers = self.handlers.borrow_mut();
+            handlers.push(Handler {
+                effect: handle.effect,
+                resumed,
+                replayed: 0,
+            });
+            handlers.len() - 1
+        };
+        let value = self.block(env, &handle.body);
+        let handler = self.handlers.borrow_mut().pop();
+
+        let performed = match value {
+            Err(performed) if performed.depth == depth => performed,
+            value => return value,
+        };
+        let clause = handle
+            .clauses
+            .iter()
+            .find(|clause| clause.operation == performed.operation)
+            .expect("type checking ensures every operation has a clause");
+
+        let mut clause_env = env.clone();
+        for (param, arg) in clause.params.iter().zip(performed.args) {
+            clause_env.insert(param.name, Binding::Value(arg));
+        }
+        let resumption = Resumption {
+            handle,
+            env: env.clone(),
+            resumed: handler.map(|handler| handler.resumed).unwrap_or_default(),
+        };
+        clause_env.insert(clause.resume, Binding::Resume(Rc::new(resumption)));
+
+        self.expr(&clause_env, &clause.body)
+    }
+
+    fn builtin(&self, env: &Env<'h>, call: &'h FunctionCallExpr) -> Result<Value, Performed> {
+        let (tag, children) = Generator::html_std(call);
+        let mut rendered = Vec::new();
+        for child in children {
+            match self.expr(env, child)? {
+                Value::Runtime(runtime) => self.needed(runtime, child.span(), "rendered here"),
+                value => rendered.extend(value.into_child()),
+            }
+        }
+
+        Ok(Value::Html(Element {
+            name: tag.to_owned(),
+            children: rendered,
+        }))
+    }
+
+    fn expr(&self, env: &Env<'h>, expr: &'h Expr) -> Result<Value, Performed> {
+        let value = match expr {
+            Expr::StringLiteral(string_literal) => Value::String(string_literal.value.clone()),
+            Expr::IntLiteral(int_literal) => Value::Int(int_literal.value),
+            Expr::FloatLiteral(float_literal) => Value::Float(float_literal.value),
+            Expr::BoolLiteral(bool_literal) => Value::Bool(bool_literal.value),
+            Expr::Error(_) => unreachable!("code is never generated for ASTs with errors"),
+            Expr::Block(block) => return self.block(env, block),
+            Expr::Handle(handle) => return self.handle(env, handle, Vec::new()),
+            Expr::Unit(_) => Value::Nothing,
+            Expr::If(if_expr) => return self.if_expr(env, if_expr),
+            Expr::Match(match_expr) => return self.match_expr(env, match_expr),
+            Expr::Variable(variable) => {
+                return self.force(
+                    env.get(&variable.name)
+                        .expect("variables to be bound before they're rendered"),
+                )
+            }
+            Expr::FunctionCall(call) => return self.call(env, call),
+        };
+
+        Ok(value)
+    }
+
+    /// Only the branch the condition picks is rendered, which can't be picked while rendering when
+    /// the condition is only known at runtime
+    fn if_expr(&self, env: &Env<'h>, if_expr: &'h IfExpr) -> Result<Value, Performed> {
+        let condition = match self.expr(env, &if_expr.condition)? {
+            Value::Bool(condition) => condition,
+            Value::Runtime(runtime) => {
+                let span = if_expr.condition.span();
+                self.needed(runtime, span, "decides what's rendered here");
+                return Ok(Value::Runtime(runtime));
+            }
+            _ => unreachable!("type checking ensures conditions are Bool"),
+        };
+
+        match (condition, &if_expr.else_branch) {
+            (true, _) => self.block(env, &if_expr.then_branch),
+            (false, Some(else_branch)) => self.expr(env, else_branch),
+            (false, None) => Ok(Value::Nothing),
+        }
+    }
+
+    fn match_expr(&self, env: &Env<'h>, match_expr: &'h MatchExpr) -> Result<Value, Performed> {
+        let value = self.expr(env, &match_expr.scrutinee)?;
+
+        // Values that are only known at runtime can still be bound, but not compared to anything,
+        // which only matters when none of the arms before match
+        let mut matched = None;
+        for arm in &match_expr.arms {
+            if let Some(runtime) = compared_runtime(&arm.pattern, &value) {
+                let span = arm.pattern.span();
+                self.needed(runtime, span, "compared to this while rendering");
+                return Ok(Value::Runtime(runtime));
+            }
+
+            let mut env = env.clone();
+            if pattern_matches(&arm.pattern, &value, &mut env) {
+                matched = Some((arm, env));
+                break;
+            }
+        }
+        let (arm, env) = matched.expect("type checking ensures matches are exhaustive");
+
+        self.expr(&env, &arm.body)
+    }
+}
+
+/// Whether a value matches a pattern, binding the names the pattern introduces when it does
+fn pattern_matches(pattern: &Pattern, value: &Value, env: &mut Env) -> bool {
+    match (pattern, value) {
+        (Pattern::Wildcard(_), _) => true,
+        (Pattern::Binding(name), value) => {
+            env.insert(name.name, Binding::Value(value.clone()));
+            true
+        }
+        (Pattern::StringLiteral(string_literal), Value::String(string)) => {
+            string_literal.value == *string
+        }
+        (Pattern::IntLiteral(int_literal), Value::Int(int)) => int_literal.value == *int,
+        (Pattern::BoolLiteral(bool_literal), Value::Bool(bool)) => bool_literal.value == *bool,
+        (Pattern::Constructor(constructor), Value::Constructed(id, fields)) => {
+            constructor.name == *id
+                && (constructor.fields.iter())
+                    .zip(fields)
+                    .all(|(pattern, value)| pattern_matches(pattern, value, env))
+        }
+        (Pattern::Error(_), _) => unreachable!("code is never generated for ASTs with errors"),
+        _ => unreachable!("type checking ensures patterns match the type of the value"),
+    }
+}
+
+/// Where a value that's only known at runtime came from when a pattern would have to look at it to
+/// tell whether it matches
+fn compared_runtime(pattern: &Pattern, value: &Value) -> Option<Runtime> {
+    match (pattern, value) {
+        (Pattern::Wildcard(_) | Pattern::Binding(_), _) => None,
+        (_, Value::Runtime(runtime)) => Some(*runtime),
+        (Pattern::Constructor(constructor), Value::Constructed(id, fields))
+            if constructor.name == *id =>
+        {
+            (constructor.fields.iter())
+                .zip(fields)
+                .find_map(|(pattern, value)| compared_runtime(pattern, value))
+        }
+        _ => None,
+    }
+}
+
+/// Whether a function performs effects from std, which only the runtime can perform
+fn performs_std(hir: &Hir, function: &Function) -> bool {
+    function
+        .output
+        .effects
+        .iter()
+        .any(|(effect, _)| !matches!(hir.module.get(&effect), Some(ModuleItem::Effect(_))))
+}
+
+/// Where the value of a block comes from, the block itself when it doesn't have a return
+/// expression
+fn block_value_span(block: &BlockExpr) -> Span {
+    block
+        .return_expression
+        .as_ref()
+        .map_or(block.span, Expr::span)
+}
@@ -0,0 +1,85 @@
+//! Analyses of which functions end up calling which, starting from the graph made by
+//! [generate_call_graph]
+
+use std::collections::HashSet;
+
+use diagnostics::{codes, Diagnostic};
+use petgraph::{algo::kosaraju_scc, graphmap::DiGraphMap, visit::Dfs};
+
+use crate::{
+    generate_call_graph,
+    hir::{self, Hir, Id},
+};
+
+/// The ids of the functions of a file, in the order they're declared in
+fn functions(hir: &Hir) -> impl Iterator<Item = (Id, &hir::Function)> {
+    hir.module.iter().filter_map(|(id, item)| match item {
+        hir::ModuleItem::Function(function) => Some((*id, &**function)),
+        hir::ModuleItem::Effect(_) => None,
+    })
+}
+
+/// The functions that `main` calls, directly or through other functions, along with `main`
+/// itself
+///
+/// Empty when there's no `main` to start from
+pub fn reachable(hir: &Hir, graph: &DiGraphMap<Id, ()>) -> HashSet<Id> {
+    let Some(main) =
+        functions(hir).find_map(|(id, function)| (function.name == "main").then_some(id))
+    else {
+        return HashSet::new();
+    };
+
+    let mut reachable = HashSet::new();
+    let mut dfs = Dfs::new(graph, main);
+    while let Some(id) = dfs.next(graph) {
+        reachable.insert(id);
+    }
+    reachable.retain(|id| hir.module.contains_key(id));
+    reachable
+}
+
+/// The groups of functions that call themselves, either directly or by calling each other in a
+/// cycle
+///
+/// Each group and the functions in it are in the order they're declared in
+pub fn recursive(hir: &Hir, graph: &DiGraphMap<Id, ()>) -> Vec<Vec<Id>> {
+    let order = |id: &Id| hir.module.get_index_of(id);
+
+    let mut groups = kosaraju_scc(graph)
+        .into_iter()
+        .filter(|component| match &component[..] {
+            [id] => graph.contains_edge(*id, *id),
+            _ => true,
+        })
+        .map(|mut component| {
+            component.retain(|id| hir.module.contains_key(id));
+            component.sort_by_key(order);
+            component
+        })
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>();
+    groups.sort_by_key(|component| order(&component[0]));
+    groups
+}
+
+/// Warns about every function that's never called from `main`
+pub fn check(hir: &Hir) -> Vec<Diagnostic> {
+    let reachable = reachable(hir, &generate_call_graph(hir));
+    if reachable.is_empty() {
+        return Vec::new();
+    }
+
+    functions(hir)
+        .filter(|(id, _)| !reachable.contains(id))
+        .map(|(_, function)| {
+            Diagnostic::warning(
+                codes::UNREACHABLE_FUNCTION,
+                function.name_span,
+                format!("function `{}` is never called from `main`", function.name),
+            )
+            .with_label("unreachable")
+            .with_help("remove it, or call it from a function that `main` calls")
+        })
+        .collect()
+}
@@ -0,0 +1,262 @@
+//! Checks that every function declares the effects it performs, counting the ones performed by
+//! the functions it calls and leaving out the ones it handles
+
+use std::collections::{BTreeMap, HashMap};
+
+use diagnostics::{codes, Diagnostic};
+use parser::Span;
+use petgraph::algo::kosaraju_scc;
+
+use crate::{
+    generate_call_graph,
+    hir::{self, Hir, Id, Kind},
+};
+
+/// A place in a function that performs effects
+struct Site {
+    performs: Performs,
+    span: Span,
+    /// The effects handled around the site, which it doesn't perform as far as the function is
+    /// concerned
+    handled: Vec<Id>,
+}
+
+enum Performs {
+    Operation {
+        effect: Id,
+    },
+    /// Everything the called function performs
+    Call {
+        function: Id,
+    },
+}
+
+/// Where an effect is first performed in a function
+#[derive(Clone, Copy, PartialEq)]
+struct Performed {
+    span: Span,
+    /// The function it was performed by if it wasn't performed directly
+    by: Option<Id>,
+}
+
+/// Gathers the sites of a function in the order they appear in
+struct Sites<'a> {
+    hir: &'a Hir,
+    handled: Vec<Id>,
+    sites: Vec<Site>,
+}
+
+impl<'a> Sites<'a> {
+    fn of(hir: &'a Hir, function: &hir::Function) -> Vec<Site> {
+        let mut sites = Self {
+            hir,
+            handled: Vec::new(),
+            sites: Vec::new(),
+        };
+        sites.block(&function.body);
+        sites.sites
+    }
+
+    fn block(&mut self, block: &hir::BlockExpr) {
+        for statement in &block.statements {
+            match statement {
+                hir::Statement::Let(let_statement) => self.expr(&let_statement.value),
+                hir::Statement::ExprStatement(expr) => self.expr(expr),
+            }
+        }
+        if let Some(expr) = &block.return_expression {
+            self.expr(expr);
+        }
+    }
+
+    fn expr(&mut self, expr: &hir::Expr) {
+        match expr {
+            hir::Expr::StringLiteral(_)
+            | hir::Expr::IntLiteral(_)
+            | hir::Expr::FloatLiteral(_)
+            | hir::Expr::BoolLiteral(_)
+            | hir::Expr::Variable(_)
+            | hir::Expr::Unit(_)
+            | hir::Expr::Error(_) => {}
+            hir::Expr::FunctionCall(call) => {
+                // Arguments are performed before the call itself
+                call.args.iter().for_each(|arg| self.expr(arg));
+
+                let performs = match (
+                    self.hir.module.get(&call.name),
+                    self.hir.operation(call.name),
+                ) {
+                    (Some(hir::ModuleItem::Function(_)), _) => Some(Performs::Call {
+                        function: call.name,
+                    }),
+                    (_, Some((effect, _))) => Some(Performs::Operation { effect }),
+                    _ => match hir::std_kind(call.name) {
+                        Some(Kind::Operation(effect)) => Some(Performs::Operation { effect }),
+                        _ => None,
+                    },
+                };
+                if let Some(performs) = performs {
+                    self.sites.push(Site {
+                        performs,
+                        span: call.span,
+                        handled: self.handled.clone(),
+                    });
+                }
+
+                call.children.iter().for_each(|child| self.expr(child));
+            }
+            hir::Expr::Block(block) => self.block(block),
+            hir::Expr::If(if_expr) => {
+                self.expr(&if_expr.condition);
+                self.block(&if_expr.then_branch);
+                if let Some(else_branch) = &if_expr.else_branch {
+                    self.expr(else_branch);
+                }
+            }
+            hir::Expr::Match(match_expr) => {
+                self.expr(&match_expr.scrutinee);
+                match_expr.arms.iter().for_each(|arm| self.expr(&arm.body));
+            }
+            hir::Expr::Handle(handle) => {
+                self.handled.push(handle.effect);
+                self.block(&handle.body);
+                self.handled.pop();
+
+                // Clauses run outside of the handler, so what they perform isn't handled by it
+                handle
+                    .clauses
+                    .iter()
+                    .for_each(|clause| self.expr(&clause.body));
+            }
+        }
+    }
+}
+
+/// The effects a function performs given what's known to be performed by the functions it calls
+fn performed_by(
+    sites: &[Site],
+    performed: &HashMap<Id, BTreeMap<Id, Performed>>,
+) -> BTreeMap<Id, Performed> {
+    let mut effects = BTreeMap::new();
+    for site in sites {
+        let reached = match site.performs {
+            Performs::Operation { effect } => vec![(effect, None)],
+            Performs::Call { function } => performed
+                .get(&function)
+                .into_iter()
+                .flat_map(|effects| effects.keys())
+                .map(|&effect| (effect, Some(function)))
+                .collect(),
+        };
+
+        for (effect, by) in reached {
+            if !site.handled.contains(&effect) {
+                effects.entry(effect).or_insert(Performed {
+                    span: site.span,
+                    by,
+                });
+            }
+        }
+    }
+    effects
+}
+
+fn is_effect(hir: &Hir, id: Id) -> bool {
+    matches!(hir.module.get(&id), Some(hir::ModuleItem::Effect(_)))
+        || hir::std_kind(id) == Some(Kind::Effect)
+}
+
+/// Checks that functions declare every effect they perform and warns about the ones they declare
+/// without ever performing
+pub fn check(hir: &Hir) -> Vec<Diagnostic> {
+    let functions = hir
+        .module
+        .iter()
+        .filter_map(|(id, item)| match item {
+            hir::ModuleItem::Function(function) => Some((*id, function)),
+            hir::ModuleItem::Effect(_) => None,
+        })
+        .collect::<Vec<_>>();
+    let sites = functions
+        .iter()
+        .map(|&(id, function)| (id, Sites::of(hir, function)))
+        .collect::<HashMap<_, _>>();
+
+    // Strongly connected components come out with the functions they call before them, so each
+    // one only needs to settle the functions that call each other within it
+    let mut performed = HashMap::new();
+    for component in kosaraju_scc(&generate_call_graph(hir)) {
+        let component = component
+            .into_iter()
+            .filter(|id| sites.contains_key(id))
+            .collect::<Vec<_>>();
+
+        loop {
+            let mut changed = false;
+            for id in &component {
+                let effects = performed_by(&sites[id], &performed);
+                if performed.get(id) != Some(&effects) {
+                    performed.insert(*id, effects);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    let name = |id: Id| hir.id_map.get(&id).map_or("{unknown}", |name| name);
+    let mut diagnostics = Vec::new();
+    for (id, function) in functions {
+        let effects = &performed[&id];
+
+        let mut undeclared = effects
+            .iter()
+            .filter(|(&effect, _)| !function.output.effects.contains(effect))
+            .collect::<Vec<_>>();
+        undeclared.sort_by_key(|(_, performed)| performed.span.start);
+        for (&effect, performed) in undeclared {
+            let label = match performed.by {
+                None => format!("`{}` is performed here", name(effect)),
+                Some(by) => format!("`{}` is performed by `{}`", name(effect), name(by)),
+            };
+            diagnostics.push(
+                Diagnostic::error(
+                    codes::UNDECLARED_EFFECT,
+                    performed.span,
+                    format!(
+                        "`{}` performs the `{}` effect without declaring it",
+                        function.name,
+                        name(effect)
+                    ),
+                )
+                .with_label(label)
+                .with_help(format!(
+                    "add it after `eff` in the signature of `{}`, or handle it with `handle`",
+                    function.name
+                )),
+            );
+        }
+
+        for (effect, span) in function.output.effects.iter() {
+            if is_effect(hir, effect) && !effects.contains_key(&effect) {
+                diagnostics.push(
+                    Diagnostic::warning(
+                        codes::UNUSED_EFFECT,
+                        span,
+                        format!(
+                            "`{}` declares the `{}` effect but never performs it",
+                            function.name,
+                            name(effect)
+                        ),
+                    )
+                    .with_label("never performed")
+                    .with_help("remove it from the signature"),
+                );
+            }
+        }
+    }
+
+    diagnostics
+}
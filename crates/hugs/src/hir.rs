@@ -1,70 +1,578 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Arc,
-};
+use std::{collections::BTreeMap, fmt, sync::Arc};
 
-use parser::AST;
+use diagnostics::{codes, Diagnostic};
+use indexmap::IndexMap;
+use parser::{Span, AST};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 #[repr(transparent)]
 pub struct Id(u64);
 
+impl Id {
+    /// Derives an id from the path of what it names, so the same file always lowers to the same ids
+    ///
+    /// This is 64 bit FNV-1a, which is simple enough to run in a `const`
+    pub const fn from_path(path: &str) -> Id {
+        let bytes = path.as_bytes();
+        let mut hash = 0xcbf29ce484222325_u64;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+            i += 1;
+        }
+        Id(hash)
+    }
+
+    pub const HTML: Id = Id::from_path("std::html::Html");
+    pub const BODY: Id = Id::from_path("std::html::Body");
+    pub const PARAGRAPH: Id = Id::from_path("std::html::Paragraph");
+    pub const CONSOLE: Id = Id::from_path("std::console::Console");
+    pub const LOG: Id = Id::from_path("std::console::log");
+    pub const STRING: Id = Id::from_path("std::primitive::String");
+    pub const INT: Id = Id::from_path("std::primitive::Int");
+    pub const FLOAT: Id = Id::from_path("std::primitive::Float");
+    pub const BOOL: Id = Id::from_path("std::primitive::Bool");
+    pub const LIST: Id = Id::from_path("std::list::List");
+    pub const OPTION: Id = Id::from_path("std::option::Option");
+    pub const SOME: Id = Id::from_path("std::option::Some");
+    pub const NONE: Id = Id::from_path("std::option::None");
+    pub const FETCH: Id = Id::from_path("std::fetch::Fetch");
+    pub const GET: Id = Id::from_path("std::fetch::get");
+    pub const UNIT: Id = Id::from_path("std::primitive::()");
+    pub const DIALOG: Id = Id::from_path("std::dialog::Dialog");
+    pub const PROMPT: Id = Id::from_path("std::dialog::prompt");
+    /// Stands in for a name that couldn't be resolved, which was already reported while lowering
+    pub const ERROR: Id = Id::from_path("{error}");
+}
+
+/// Ids are shown in hex, which is how they show up in the call graph
+impl fmt::Display for Id {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// What sort of thing a name refers to, which decides where it can be used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A type constructor that takes this many type arguments, `0` for types like `String`
+    Type(usize),
+    Effect,
+    Function,
+    /// An operation of the effect with this id
+    Operation(Id),
+    /// A parameter or a variable
+    Local,
+    /// The `resume` of a handler clause, which unlike other locals can be called
+    Resume,
+    /// A constructor of the type with this id, which is called to make a value of it and matched
+    /// on to take one apart
+    Constructor(Id),
+}
+
+impl Kind {
+    pub fn describe(self) -> &'static str {
+        match self {
+            Kind::Type(_) => "type",
+            Kind::Effect => "effect",
+            Kind::Function => "function",
+            Kind::Operation(_) => "operation",
+            Kind::Local | Kind::Resume => "variable",
+            Kind::Constructor(_) => "constructor",
+        }
+    }
+
+    /// Whether something of this kind can be called, `Html` is a type but is called like the
+    /// elements that go in it
+    fn is_callable(self, id: Id) -> bool {
+        matches!(
+            self,
+            Kind::Function | Kind::Operation(_) | Kind::Resume | Kind::Constructor(_)
+        ) || id == Id::HTML
+    }
+}
+
+/// The name of a module of std along with the name, id and kind of each of its items
+type StdModule = (&'static str, &'static [(&'static str, Id, Kind)]);
+
+// TODO: Whacky temporary hack until we implement a proper standard library
+const STD: [StdModule; 7] = [
+    (
+        "html",
+        &[
+            ("Html", Id::HTML, Kind::Type(0)),
+            ("Body", Id::BODY, Kind::Function),
+            ("Paragraph", Id::PARAGRAPH, Kind::Function),
+        ],
+    ),
+    (
+        "console",
+        &[
+            ("Console", Id::CONSOLE, Kind::Effect),
+            ("log", Id::LOG, Kind::Operation(Id::CONSOLE)),
+        ],
+    ),
+    (
+        "primitive",
+        &[
+            ("String", Id::STRING, Kind::Type(0)),
+            ("Int", Id::INT, Kind::Type(0)),
+            ("Float", Id::FLOAT, Kind::Type(0)),
+            ("Bool", Id::BOOL, Kind::Type(0)),
+            // Not a name that can be written, `()` is parsed on its own
+            ("()", Id::UNIT, Kind::Type(0)),
+        ],
+    ),
+    (
+        "fetch",
+        &[
+            ("Fetch", Id::FETCH, Kind::Effect),
+            ("get", Id::GET, Kind::Operation(Id::FETCH)),
+        ],
+    ),
+    (
+        "dialog",
+        &[
+            ("Dialog", Id::DIALOG, Kind::Effect),
+            ("prompt", Id::PROMPT, Kind::Operation(Id::DIALOG)),
+        ],
+    ),
+    ("list", &[("List", Id::LIST, Kind::Type(1))]),
+    (
+        "option",
+        &[
+            ("Option", Id::OPTION, Kind::Type(1)),
+            ("Some", Id::SOME, Kind::Constructor(Id::OPTION)),
+            ("None", Id::NONE, Kind::Constructor(Id::OPTION)),
+        ],
+    ),
+];
+
+/// The types of the parameters of an operation from std and the type it outputs
+pub fn std_signature(id: Id) -> Option<(&'static [Id], Id)> {
+    match id {
+        Id::LOG => Some((&[Id::STRING], Id::UNIT)),
+        // Nothing waits for the response, all `get` does is send the request
+        Id::GET => Some((&[Id::STRING], Id::UNIT)),
+        Id::PROMPT => Some((&[Id::STRING], Id::STRING)),
+        _ => None,
+    }
+}
+
+/// The fields of a constructor from std, each given as the index of the type parameter of its type
+/// that the field holds
+pub fn std_fields(constructor: Id) -> Option<&'static [usize]> {
+    match constructor {
+        Id::SOME => Some(&[0]),
+        Id::NONE => Some(&[]),
+        _ => None,
+    }
+}
+
+/// The operations of an effect from std
+pub fn std_operations(effect: Id) -> impl Iterator<Item = Id> {
+    STD.iter()
+        .flat_map(|(_, items)| items.iter())
+        .filter_map(move |&(_, id, kind)| (kind == Kind::Operation(effect)).then_some(id))
+}
+
+/// The constructors of a type from std
+pub fn std_constructors(ty: Id) -> impl Iterator<Item = Id> {
+    STD.iter()
+        .flat_map(|(_, items)| items.iter())
+        .filter_map(move |&(_, id, kind)| (kind == Kind::Constructor(ty)).then_some(id))
+}
+
+/// The kind of an item from std, `None` for anything that isn't from std
+pub fn std_kind(id: Id) -> Option<Kind> {
+    STD.iter()
+        .flat_map(|(_, items)| items.iter())
+        .find_map(|&(_, std_id, kind)| (std_id == id).then_some(kind))
+}
+
+/// The items declared in a module and the modules nested in it
+#[derive(Default)]
+struct Module {
+    items: BTreeMap<Arc<str>, Id>,
+    modules: BTreeMap<Arc<str>, Module>,
+}
+
+impl Module {
+    /// Finds an item by following the modules in the path that lead to it
+    fn resolve(&self, path: &[parser::Ident]) -> Option<Id> {
+        match path {
+            [] => None,
+            [name] => self.items.get(&name.name[..]).copied(),
+            [module, rest @ ..] => self.modules.get(&module.name[..])?.resolve(rest),
+        }
+    }
+
+    /// Finds a nested module by following every module in the path
+    fn module(&self, path: &[parser::Ident]) -> Option<&Module> {
+        path.iter()
+            .try_fold(self, |module, name| module.modules.get(&name.name[..]))
+    }
+}
+
+/// How many single character insertions, deletions, substitutions and swaps of neighbouring
+/// characters it takes to turn one name into the other
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    // `distances[i][j]` is the distance between the first `i` characters of `a` and the first `j`
+    // characters of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+/// The candidate closest to a misspelled name, as long as it's close enough to be a likely typo
+fn closest<'a>(name: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let allowed = (name.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .filter(|candidate| *candidate != name)
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= allowed)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
 struct Scopes {
-    inner: Vec<BTreeMap<Arc<str>, Id>>,
+    /// What paths starting with `std::` refer to
+    std: Module,
+    /// The items of the file being compiled, which is also where paths starting with `crate::` go
+    root: Module,
+    /// The std items that can be used everywhere without a path, the root's items take precedence
+    /// over them so that a file is free to reuse their names
+    prelude: BTreeMap<Arc<str>, Id>,
+    /// Parameters and variables, with the innermost scope last
+    locals: Vec<BTreeMap<Arc<str>, Id>>,
+    /// The operations of every effect by their name, which is how handlers refer to them
+    operations: BTreeMap<Id, BTreeMap<Arc<str>, Id>>,
+    /// The kinds of the items of the file, their operations and type parameters, everything else
+    /// that isn't from std is a local
+    kinds: BTreeMap<Id, Kind>,
     reverse_map: BTreeMap<Id, Arc<str>>,
+    /// The path of the item being lowered, which the ids of its locals are derived from
+    owner: Arc<str>,
+    /// How many locals the item being lowered has declared so far, which tells apart locals that
+    /// share a name
+    owned: usize,
+    /// Names that couldn't be resolved, which are lowered to placeholders so lowering can go on
+    diagnostics: Vec<Diagnostic>,
 }
 
 impl Scopes {
-    fn with_prelude() -> Self {
-        // TODO: Whacky temporary hack until we implement a proper standard library
-        let prelude = [
-            (Arc::from("Html"), Id(0xa624256d78ea27e8)),
-            (Arc::from("Body"), Id(0xf65ea75ed430d7aa)),
-            (Arc::from("Paragraph"), Id(0x829569a9b2c10679)),
-            (Arc::from("Console"), Id(0x6f21a62dd1571f6e)),
-        ];
+    fn with_std() -> Self {
+        let mut std = Module::default();
+        let mut prelude = BTreeMap::new();
+        let mut operations = BTreeMap::<_, BTreeMap<_, _>>::new();
+        let mut reverse_map = BTreeMap::new();
+
+        for (module_name, items) in STD {
+            let module = std.modules.entry(Arc::from(module_name)).or_default();
+            for &(name, id, kind) in items {
+                let name = Arc::<str>::from(name);
+                if let Kind::Operation(effect) = kind {
+                    operations
+                        .entry(effect)
+                        .or_default()
+                        .insert(Arc::clone(&name), id);
+                }
+                module.items.insert(Arc::clone(&name), id);
+                prelude.insert(Arc::clone(&name), id);
+                let previous = reverse_map.insert(id, name);
+                assert!(previous.is_none(), "std to not have colliding ids");
+            }
+        }
 
         Scopes {
-            inner: vec![prelude.clone().into_iter().collect()],
-            reverse_map: prelude.into_iter().map(|(ident, id)| (id, ident)).collect(),
+            std,
+            root: Module::default(),
+            prelude,
+            locals: Vec::new(),
+            operations,
+            kinds: BTreeMap::new(),
+            reverse_map,
+            owner: Arc::from("crate"),
+            owned: 0,
+            diagnostics: Vec::new(),
+        }
+    }
+
+    /// Gives a name the id derived from its path, on the off chance that the id is already taken
+    /// the path is suffixed until it isn't, which only depends on what was declared before it
+    fn new_name(&mut self, ident: &str, path: &str) -> (Arc<str>, Id) {
+        let mut id = Id::from_path(path);
+        let mut attempt = 0;
+        while self.reverse_map.contains_key(&id) || id == Id::ERROR {
+            attempt += 1;
+            id = Id::from_path(&format!("{path}#{attempt}"));
         }
+        let ident = Arc::<str>::from(ident);
+        self.reverse_map.insert(id, Arc::clone(&ident));
+
+        (ident, id)
+    }
+
+    /// Makes the locals declared from now on belong to the item at the path
+    fn enter_item(&mut self, path: &str) {
+        self.owner = Arc::from(path);
+        self.owned = 0;
+    }
+
+    /// Declares an item of the file being compiled
+    fn new_item_id(&mut self, ident: &str, kind: Kind) -> Id {
+        let (ident, id) = self.new_name(ident, &format!("crate::{ident}"));
+        self.root.items.insert(ident, id);
+        self.kinds.insert(id, kind);
+
+        id
+    }
+
+    /// Declares an operation of an effect, which can be used through its effect like an item of a
+    /// module and also on its own as long as no item of the file has the same name
+    fn new_operation_id(&mut self, effect: (Id, &str), operation: &str) -> Id {
+        let (operation, id) =
+            self.new_name(operation, &format!("crate::{}::{operation}", effect.1));
+        self.root
+            .modules
+            .entry(Arc::from(effect.1))
+            .or_default()
+            .items
+            .insert(Arc::clone(&operation), id);
+        self.operations
+            .entry(effect.0)
+            .or_default()
+            .insert(Arc::clone(&operation), id);
+        self.root.items.entry(operation).or_insert(id);
+        self.kinds.insert(id, Kind::Operation(effect.0));
+
+        id
     }
 
+    fn get_operation_id(&self, effect: Id, operation: &str) -> Option<Id> {
+        self.operations.get(&effect)?.get(operation).copied()
+    }
+
+    /// Declares a parameter or variable in the innermost scope
     fn new_id(&mut self, ident: &str) -> Id {
-        let id = Id(rand::random());
+        // Locals are numbered since they can share a name with another local of the same item
+        let path = format!("{}::{ident}#{}", self.owner, self.owned);
+        self.owned += 1;
+        let (ident, id) = self.new_name(ident, &path);
+        let scope = self
+            .locals
+            .last_mut()
+            .expect("there to be a scope for locals");
+        scope.insert(ident, id);
 
-        let scope = self.inner.last_mut().expect("there to always be a scope");
-        let ident = Arc::from(ident);
-        scope.insert(Arc::clone(&ident), id);
-        self.reverse_map.insert(id, ident);
+        id
+    }
+
+    /// Declares a type parameter in the innermost scope
+    fn new_type_param_id(&mut self, ident: &str) -> Id {
+        let id = self.new_id(ident);
+        self.kinds.insert(id, Kind::Type(0));
 
         id
     }
 
-    fn get_id(&self, ident: &str) -> Option<Id> {
-        self.inner
+    /// Declares the `resume` of a handler clause in the innermost scope
+    fn new_resume_id(&mut self) -> Id {
+        let id = self.new_id("resume");
+        self.kinds.insert(id, Kind::Resume);
+
+        id
+    }
+
+    fn kind(&self, id: Id) -> Kind {
+        self.kinds
+            .get(&id)
+            .copied()
+            .or_else(|| std_kind(id))
+            .unwrap_or(Kind::Local)
+    }
+
+    /// Like [Scopes::get_id] but only finds variables and parameters, not items
+    fn get_local_id(&self, ident: &str) -> Option<Id> {
+        self.locals
             .iter()
             .rev()
             .find_map(|scope| scope.get(ident))
             .copied()
     }
 
+    fn get_id(&self, ident: &str) -> Option<Id> {
+        self.get_local_id(ident)
+            .or_else(|| self.root.items.get(ident).copied())
+            .or_else(|| self.prelude.get(ident).copied())
+    }
+
+    fn resolve(&self, path: &parser::Path) -> Option<Id> {
+        match &path.segments[..] {
+            [name] => self.get_id(&name.name),
+            [first, rest @ ..] => match &first.name[..] {
+                "std" => self.std.resolve(rest),
+                "crate" => self.root.resolve(rest),
+                _ => self.root.resolve(&path.segments),
+            },
+            [] => None,
+        }
+    }
+
+    /// The names that could have been meant by a path, which are the ones visible from where it
+    /// was written if it has a single segment and the items of the module it goes through if not
+    fn candidates(&self, path: &[parser::Ident]) -> BTreeMap<Arc<str>, Id> {
+        let Some((_, modules)) = path.split_last() else {
+            return BTreeMap::new();
+        };
+        let module = match modules {
+            [] => {
+                // Inner names are inserted last since they're the ones that end up being visible
+                return self
+                    .prelude
+                    .iter()
+                    .chain(&self.root.items)
+                    .chain(self.locals.iter().flatten())
+                    .map(|(name, id)| (Arc::clone(name), *id))
+                    .collect();
+            }
+            [first, rest @ ..] if first.name == "std" => self.std.module(rest),
+            [first, rest @ ..] if first.name == "crate" => self.root.module(rest),
+            _ => self.root.module(modules),
+        };
+        module.map_or_else(BTreeMap::new, |module| module.items.clone())
+    }
+
+    /// Reports a name that isn't in scope, suggesting the closest one that has a kind it could have
+    /// been meant as
+    fn unresolved(&mut self, what: &str, path: &parser::Path, expected: impl Fn(Id, Kind) -> bool) {
+        let written = path
+            .segments
+            .iter()
+            .map(|segment| &segment.name[..])
+            .collect::<Vec<_>>()
+            .join("::");
+        let diagnostic = Diagnostic::error(
+            codes::UNRESOLVED_NAME,
+            path.span,
+            format!("cannot find {what} `{written}` in this scope"),
+        )
+        .with_label("not found in this scope");
+
+        let candidates = self.candidates(&path.segments);
+        let suggestion = closest(
+            &path.name().name,
+            candidates
+                .iter()
+                .filter(|(_, id)| expected(**id, self.kind(**id)))
+                .map(|(name, _)| &name[..]),
+        )
+        .map(|suggestion| match written.rfind("::") {
+            Some(end) => format!("{}::{suggestion}", &written[..end]),
+            None => suggestion.to_string(),
+        });
+
+        self.diagnostics.push(match suggestion {
+            Some(suggestion) => diagnostic.with_help(format!("did you mean `{suggestion}`?")),
+            None => diagnostic,
+        });
+    }
+
+    fn resolve_ty(&mut self, path: &parser::Path) -> Id {
+        self.resolve(path).unwrap_or_else(|| {
+            self.unresolved("type", path, |_, kind| matches!(kind, Kind::Type(_)));
+            Id::ERROR
+        })
+    }
+
+    fn unresolved_operation(&mut self, effect: Id, operation: &parser::Ident) {
+        let effect_name = self
+            .reverse_map
+            .get(&effect)
+            .map_or("{unknown}", |name| name);
+        let diagnostic = Diagnostic::error(
+            codes::UNRESOLVED_NAME,
+            operation.span,
+            format!(
+                "cannot find operation `{}` in `{effect_name}`",
+                operation.name
+            ),
+        )
+        .with_label(format!("not an operation of `{effect_name}`"));
+
+        let suggestion = self.operations.get(&effect).and_then(|operations| {
+            closest(&operation.name, operations.keys().map(|name| &name[..]))
+        });
+        self.diagnostics.push(match suggestion {
+            Some(suggestion) => diagnostic.with_help(format!("did you mean `{suggestion}`?")),
+            None => diagnostic,
+        });
+    }
+
+    /// Resolves what a call calls, reporting names that aren't in scope or aren't callable
+    fn resolve_callee(&mut self, what: &str, path: &parser::Path) -> Option<Id> {
+        let Some(id) = self.resolve(path) else {
+            self.unresolved(what, path, |id, kind| kind.is_callable(id));
+            return None;
+        };
+
+        let kind = self.kind(id);
+        if kind.is_callable(id) {
+            return Some(id);
+        }
+        self.diagnostics.push(
+            Diagnostic::error(
+                codes::NOT_A_FUNCTION,
+                path.span,
+                format!(
+                    "expected function, found {} `{}`",
+                    kind.describe(),
+                    self.reverse_map.get(&id).map_or("{unknown}", |name| name)
+                ),
+            )
+            .with_label("not a function"),
+        );
+        None
+    }
+
     fn new_scope(&mut self) {
-        self.inner.push(BTreeMap::new())
+        self.locals.push(BTreeMap::new())
     }
 
     fn pop_scope(&mut self) {
-        self.inner.pop();
+        self.locals.pop();
     }
 
-    fn into_id_map(self) -> BTreeMap<Id, Arc<str>> {
-        self.reverse_map
+    fn into_id_map(self) -> (BTreeMap<Id, Arc<str>>, Vec<Diagnostic>) {
+        (self.reverse_map, self.diagnostics)
     }
 }
 
 #[derive(Debug, PartialEq)]
 pub struct Hir {
-    pub module: HashMap<Id, ModuleItem>,
+    pub module: IndexMap<Id, ModuleItem>,
     pub id_map: BTreeMap<Id, Arc<str>>,
 }
 
@@ -73,32 +581,48 @@ pub struct FunctionCallExpr {
     pub name: Id,
     pub args: Vec<Expr>,
     pub children: Vec<Expr>,
+    pub span: Span,
 }
 
 impl FunctionCallExpr {
-    fn lower(scopes: &mut Scopes, call: &parser::FunctionCallExpr) -> Self {
-        Self {
-            name: scopes
-                .get_id(&call.name)
-                .expect("function calls to call something in scope"),
-            args: call
-                .args
-                .iter()
-                .map(|expr| Expr::lower(scopes, expr))
-                .collect(),
-            children: call
-                .children
-                .iter()
-                .map(|expr| Expr::lower(scopes, expr))
-                .collect(),
+    /// Calls to something that can't be called are lowered to [Expr::Error], after their arguments
+    /// and children so that what's wrong with those is still found
+    fn lower(scopes: &mut Scopes, call: &parser::FunctionCallExpr) -> Expr {
+        let name = scopes.resolve_callee("function", &call.name);
+        let args = call
+            .args
+            .iter()
+            .map(|expr| Expr::lower(scopes, expr))
+            .collect();
+        let children = call
+            .children
+            .iter()
+            .map(|expr| Expr::lower(scopes, expr))
+            .collect();
+
+        match name {
+            Some(name) => Expr::FunctionCall(Self {
+                name,
+                args,
+                children,
+                span: call.span,
+            }),
+            None => Expr::Error(call.span),
         }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct VariableExpr {
+    pub name: Id,
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct BlockExpr {
     pub statements: Vec<Statement>,
     pub return_expression: Option<Expr>,
+    pub span: Span,
 }
 
 impl BlockExpr {
@@ -115,6 +639,7 @@ impl BlockExpr {
                 .iter()
                 .map(|expr| Expr::lower(scopes, expr))
                 .next(),
+            span: block.span,
         };
         scopes.pop_scope();
 
@@ -122,32 +647,421 @@ impl BlockExpr {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct IfExpr {
+    pub condition: Expr,
+    pub then_branch: BlockExpr,
+    /// Either a block or another `if` when it's an `else if`
+    pub else_branch: Option<Expr>,
+    pub span: Span,
+}
+
+impl IfExpr {
+    fn lower(scopes: &mut Scopes, if_expr: &parser::IfExpr) -> Self {
+        Self {
+            condition: Expr::lower(scopes, &if_expr.condition),
+            then_branch: BlockExpr::lower(scopes, &if_expr.then_branch),
+            else_branch: if_expr
+                .else_branch
+                .as_ref()
+                .map(|expr| Expr::lower(scopes, expr)),
+            span: if_expr.span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BindingPattern {
+    pub name: Id,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ConstructorPattern {
+    pub name: Id,
+    pub fields: Vec<Pattern>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Pattern {
+    Wildcard(Span),
+    Binding(BindingPattern),
+    StringLiteral(StringLiteralExpr),
+    IntLiteral(IntLiteralExpr),
+    BoolLiteral(BoolLiteralExpr),
+    Constructor(ConstructorPattern),
+    Error(Span),
+}
+
+impl Pattern {
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Wildcard(span) | Pattern::Error(span) => *span,
+            Pattern::Binding(binding) => binding.span,
+            Pattern::StringLiteral(string_literal) => string_literal.span,
+            Pattern::IntLiteral(int_literal) => int_literal.span,
+            Pattern::BoolLiteral(bool_literal) => bool_literal.span,
+            Pattern::Constructor(constructor) => constructor.span,
+        }
+    }
+
+    /// Bindings are introduced into the current scope, which is expected to be the arm's own
+    fn lower(scopes: &mut Scopes, pattern: &parser::Pattern) -> Self {
+        match pattern {
+            parser::Pattern::Wildcard(span) => Pattern::Wildcard(*span),
+            // Like in expressions, a constructor without fields is written on its own
+            parser::Pattern::Binding(name) => match scopes.get_id(&name.name) {
+                Some(id) if matches!(scopes.kind(id), Kind::Constructor(_)) => {
+                    Pattern::Constructor(ConstructorPattern {
+                        name: id,
+                        fields: Vec::new(),
+                        span: name.span,
+                    })
+                }
+                _ => Pattern::Binding(BindingPattern {
+                    name: scopes.new_id(&name.name),
+                    span: name.span,
+                }),
+            },
+            parser::Pattern::StringLiteral(string_literal) => {
+                Pattern::StringLiteral(StringLiteralExpr {
+                    value: string_literal.value.clone(),
+                    span: string_literal.span,
+                })
+            }
+            parser::Pattern::IntLiteral(int_literal) => Pattern::IntLiteral(IntLiteralExpr {
+                value: int_literal.value,
+                span: int_literal.span,
+            }),
+            parser::Pattern::BoolLiteral(bool_literal) => Pattern::BoolLiteral(BoolLiteralExpr {
+                value: bool_literal.value,
+                span: bool_literal.span,
+            }),
+            parser::Pattern::Constructor(constructor) => Pattern::Constructor(ConstructorPattern {
+                name: scopes.get_id(&constructor.name.name).unwrap_or_else(|| {
+                    let path = parser::Path {
+                        segments: vec![parser::Ident {
+                            name: constructor.name.name.clone(),
+                            span: constructor.name.span,
+                        }],
+                        span: constructor.name.span,
+                    };
+                    scopes.unresolved("constructor", &path, |_, kind| {
+                        matches!(kind, Kind::Constructor(_))
+                    });
+                    Id::ERROR
+                }),
+                fields: constructor
+                    .fields
+                    .iter()
+                    .map(|field| Pattern::lower(scopes, field))
+                    .collect(),
+                span: constructor.span,
+            }),
+            parser::Pattern::Error(span) => Pattern::Error(*span),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MatchExpr {
+    pub scrutinee: Expr,
+    pub arms: Vec<MatchArm>,
+    pub span: Span,
+}
+
+impl MatchExpr {
+    fn lower(scopes: &mut Scopes, match_expr: &parser::MatchExpr) -> Self {
+        let scrutinee = Expr::lower(scopes, &match_expr.scrutinee);
+
+        // Every arm gets its own scope for the names its pattern binds
+        let arms = match_expr
+            .arms
+            .iter()
+            .map(|arm| {
+                scopes.new_scope();
+                let pattern = Pattern::lower(scopes, &arm.pattern);
+                let body = Expr::lower(scopes, &arm.body);
+                scopes.pop_scope();
+
+                MatchArm {
+                    pattern,
+                    body,
+                    span: arm.span,
+                }
+            })
+            .collect();
+
+        Self {
+            scrutinee,
+            arms,
+            span: match_expr.span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HandlerClause {
+    pub operation: Id,
+    pub params: Vec<BindingPattern>,
+    /// The function that continues the handled block from where it performed the operation
+    pub resume: Id,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct HandleExpr {
+    pub body: BlockExpr,
+    pub effect: Id,
+    pub effect_span: Span,
+    pub clauses: Vec<HandlerClause>,
+    pub span: Span,
+}
+
+impl HandleExpr {
+    /// Handles of an effect that isn't in scope are lowered to [Expr::Error], after their body and
+    /// clauses so that what's wrong with those is still found
+    fn lower(scopes: &mut Scopes, handle: &parser::HandleExpr) -> Expr {
+        let body = BlockExpr::lower(scopes, &handle.body);
+        let effect = scopes.resolve(&handle.effect);
+        if effect.is_none() {
+            scopes.unresolved("effect", &handle.effect, |_, kind| kind == Kind::Effect);
+        }
+        // Operations are only looked up in effects, anything else is reported by the type checker
+        let operations = effect.filter(|effect| scopes.kind(*effect) == Kind::Effect);
+
+        // Like match arms, every clause gets its own scope for its params and `resume`
+        let clauses = handle
+            .clauses
+            .iter()
+            .map(|clause| {
+                let operation = match operations {
+                    Some(effect) => scopes
+                        .get_operation_id(effect, &clause.operation.name)
+                        .unwrap_or_else(|| {
+                            scopes.unresolved_operation(effect, &clause.operation);
+                            Id::ERROR
+                        }),
+                    None => Id::ERROR,
+                };
+
+                scopes.new_scope();
+                let params = clause
+                    .params
+                    .iter()
+                    .map(|param| BindingPattern {
+                        name: scopes.new_id(&param.name),
+                        span: param.span,
+                    })
+                    .collect();
+                let resume = scopes.new_resume_id();
+                let body = Expr::lower(scopes, &clause.body);
+                scopes.pop_scope();
+
+                HandlerClause {
+                    operation,
+                    params,
+                    resume,
+                    body,
+                    span: clause.span,
+                }
+            })
+            .collect();
+
+        match effect {
+            Some(effect) => Expr::Handle(Box::new(Self {
+                body,
+                effect,
+                effect_span: handle.effect.span,
+                clauses,
+                span: handle.span,
+            })),
+            None => Expr::Error(handle.span),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct StringLiteralExpr {
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct IntLiteralExpr {
+    pub value: i64,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct FloatLiteralExpr {
+    pub value: f64,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BoolLiteralExpr {
+    pub value: bool,
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Expr {
-    StringLiteral(String),
+    StringLiteral(StringLiteralExpr),
+    IntLiteral(IntLiteralExpr),
+    FloatLiteral(FloatLiteralExpr),
+    BoolLiteral(BoolLiteralExpr),
+    Variable(VariableExpr),
     FunctionCall(FunctionCallExpr),
-    // TODO: Handle BlockExpr
+    Block(Box<BlockExpr>),
+    If(Box<IfExpr>),
+    Match(Box<MatchExpr>),
+    Handle(Box<HandleExpr>),
+    Unit(Span),
+    /// An expression that failed to parse, kept around so the rest of the module can still be lowered
+    Error(Span),
 }
 
 impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::StringLiteral(string_literal) => string_literal.span,
+            Expr::IntLiteral(int_literal) => int_literal.span,
+            Expr::FloatLiteral(float_literal) => float_literal.span,
+            Expr::BoolLiteral(bool_literal) => bool_literal.span,
+            Expr::Variable(variable) => variable.span,
+            Expr::FunctionCall(call) => call.span,
+            Expr::Block(block) => block.span,
+            Expr::If(if_expr) => if_expr.span,
+            Expr::Match(match_expr) => match_expr.span,
+            Expr::Handle(handle) => handle.span,
+            Expr::Unit(span) => *span,
+            Expr::Error(span) => *span,
+        }
+    }
+
     fn lower(scopes: &mut Scopes, expr: &parser::Expr) -> Self {
         match expr {
-            parser::Expr::StringLiteral(string) => Expr::StringLiteral(string.clone()),
-            parser::Expr::FunctionCall(call) => {
-                Expr::FunctionCall(FunctionCallExpr::lower(scopes, call))
+            parser::Expr::StringLiteral(string_literal) => Expr::StringLiteral(StringLiteralExpr {
+                value: string_literal.value.clone(),
+                span: string_literal.span,
+            }),
+            parser::Expr::IntLiteral(int_literal) => Expr::IntLiteral(IntLiteralExpr {
+                value: int_literal.value,
+                span: int_literal.span,
+            }),
+            parser::Expr::FloatLiteral(float_literal) => Expr::FloatLiteral(FloatLiteralExpr {
+                value: float_literal.value,
+                span: float_literal.span,
+            }),
+            parser::Expr::BoolLiteral(bool_literal) => Expr::BoolLiteral(BoolLiteralExpr {
+                value: bool_literal.value,
+                span: bool_literal.span,
+            }),
+            parser::Expr::Variable(name) => match scopes.get_local_id(&name.name) {
+                // Resuming is the only thing a `resume` can do, it isn't a value of its own
+                Some(id) if scopes.kind(id) == Kind::Resume => {
+                    scopes.diagnostics.push(
+                        Diagnostic::error(
+                            codes::RESUME_NOT_CALLED,
+                            name.span,
+                            "`resume` can only be called",
+                        )
+                        .with_label("used as a value here")
+                        .with_help("call it with the value to resume with, as in `resume(value)`"),
+                    );
+                    Expr::Error(name.span)
+                }
+                Some(id) => Expr::Variable(VariableExpr {
+                    name: id,
+                    span: name.span,
+                }),
+                // A name that isn't a variable is a function being called without any arguments
+                None => {
+                    let path = parser::Path {
+                        segments: vec![parser::Ident {
+                            name: name.name.clone(),
+                            span: name.span,
+                        }],
+                        span: name.span,
+                    };
+                    // Misspelled variables are as likely as misspelled functions here
+                    if scopes.resolve(&path).is_none() {
+                        scopes.unresolved("value", &path, |id, kind| {
+                            kind == Kind::Local || kind.is_callable(id)
+                        });
+                        return Expr::Error(name.span);
+                    }
+                    match scopes.resolve_callee("value", &path) {
+                        Some(id) => Expr::FunctionCall(FunctionCallExpr {
+                            name: id,
+                            args: Vec::new(),
+                            children: Vec::new(),
+                            span: name.span,
+                        }),
+                        None => Expr::Error(name.span),
+                    }
+                }
+            },
+            parser::Expr::FunctionCall(call) => FunctionCallExpr::lower(scopes, call),
+            parser::Expr::Block(block) => Expr::Block(Box::new(BlockExpr::lower(scopes, block))),
+            parser::Expr::If(if_expr) => Expr::If(Box::new(IfExpr::lower(scopes, if_expr))),
+            parser::Expr::Match(match_expr) => {
+                Expr::Match(Box::new(MatchExpr::lower(scopes, match_expr)))
             }
+            parser::Expr::Handle(handle) => HandleExpr::lower(scopes, handle),
+            parser::Expr::Unit(span) => Expr::Unit(*span),
+            parser::Expr::Error(span) => Expr::Error(*span),
         }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct LetStatement {
+    pub name: Id,
+    pub ty: Option<Ty>,
+    pub value: Expr,
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum Statement {
+    Let(LetStatement),
     ExprStatement(Expr),
 }
 
 impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(let_statement) => let_statement.span,
+            Statement::ExprStatement(expr) => expr.span(),
+        }
+    }
+
     fn lower(scopes: &mut Scopes, statement: &parser::Statement) -> Self {
         match statement {
+            parser::Statement::Let(let_statement) => {
+                // The value is lowered before the name is introduced so that it still refers to
+                // whatever the name is shadowing, as in `let x = x;`
+                let value = Expr::lower(scopes, &let_statement.value);
+                let ty = let_statement.ty.as_ref().map(|ty| Ty::lower(scopes, ty));
+
+                Statement::Let(LetStatement {
+                    name: scopes.new_id(&let_statement.name.name),
+                    ty,
+                    value,
+                    span: let_statement.span,
+                })
+            }
             parser::Statement::ExprStatement(expr) => {
                 Statement::ExprStatement(Expr::lower(scopes, expr))
             }
@@ -155,70 +1069,299 @@ impl Statement {
     }
 }
 
+/// A type as it's written in a signature or a `let`
+#[derive(Debug, PartialEq)]
+pub struct Ty {
+    pub name: Id,
+    /// The type arguments of a generic type, empty for every other type
+    pub args: Vec<Ty>,
+    pub span: Span,
+}
+
+impl Ty {
+    /// Types that aren't in scope are named [Id::ERROR]
+    fn lower(scopes: &mut Scopes, ty: &parser::Ty) -> Self {
+        match ty {
+            parser::Ty::Path(path) => Ty {
+                name: scopes.resolve_ty(path),
+                args: Vec::new(),
+                span: path.span,
+            },
+            parser::Ty::Unit(span) => Ty {
+                name: Id::UNIT,
+                args: Vec::new(),
+                span: *span,
+            },
+            parser::Ty::Generic(generic) => Ty {
+                name: scopes.resolve_ty(&generic.constructor),
+                args: generic
+                    .args
+                    .iter()
+                    .map(|arg| Ty::lower(scopes, arg))
+                    .collect(),
+                span: generic.span,
+            },
+        }
+    }
+}
+
+/// The effects a function is allowed to perform
+///
+/// The order they're listed in doesn't matter, `eff Console + Fetch` and `eff Fetch + Console` are
+/// the same row, and an effect that's listed twice is only in the row once
+#[derive(Debug, Default)]
+pub struct EffectRow {
+    /// Where each effect was first listed
+    effects: BTreeMap<Id, Span>,
+}
+
+impl EffectRow {
+    pub fn is_empty(&self) -> bool {
+        self.effects.is_empty()
+    }
+
+    pub fn contains(&self, effect: Id) -> bool {
+        self.effects.contains_key(&effect)
+    }
+
+    /// Every effect in the row along with where it was listed, in the order they were listed
+    pub fn iter(&self) -> impl Iterator<Item = (Id, Span)> {
+        let mut effects = self
+            .effects
+            .iter()
+            .map(|(effect, span)| (*effect, *span))
+            .collect::<Vec<_>>();
+        effects.sort_by_key(|(_, span)| span.start);
+
+        effects.into_iter()
+    }
+}
+
+impl PartialEq for EffectRow {
+    fn eq(&self, other: &Self) -> bool {
+        self.effects.keys().eq(other.effects.keys())
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct FunctionOutput {
-    pub ty: Id,
-    pub eff: Option<Id>,
+    pub ty: Ty,
+    pub effects: EffectRow,
+    pub span: Span,
 }
 
 impl FunctionOutput {
-    fn lower(scopes: &Scopes, output: &parser::FunctionOutput) -> Self {
-        // TODO: lowering should return results
-        let ty = match &output.ty {
-            parser::Ty::Simple(ty) => scopes
-                .get_id(ty)
-                .expect("FunctionOutput to have a ty in scope"),
-        };
-        let eff = match &output.eff {
-            None => None,
-            Some(parser::Eff::Simple(eff)) => scopes
-                .get_id(eff)
-                .expect("FunctionOutput to have an effect in scope")
-                .into(),
-        };
+    /// Effects that aren't in scope are left out of the row
+    fn lower(scopes: &mut Scopes, output: &parser::FunctionOutput) -> Self {
+        let ty = Ty::lower(scopes, &output.ty);
+        let mut effects = EffectRow::default();
+        for eff in &output.effects {
+            let parser::Eff::Path(path) = eff;
+            match scopes.resolve(path) {
+                Some(effect) => {
+                    effects.effects.entry(effect).or_insert(path.span);
+                }
+                None => scopes.unresolved("effect", path, |_, kind| kind == Kind::Effect),
+            }
+        }
 
-        Self { ty, eff }
+        Self {
+            ty,
+            effects,
+            span: output.span,
+        }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct Param {
+    pub name: Id,
+    pub ty: Ty,
+    pub span: Span,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Function {
     pub name: String,
-    pub inputs: (),
+    pub name_span: Span,
+    pub generics: Vec<TypeParam>,
+    pub inputs: Vec<Param>,
     pub output: FunctionOutput,
     pub body: BlockExpr,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TypeParam {
+    pub name: Id,
+    pub span: Span,
+}
+
+impl Function {
+    fn lower(scopes: &mut Scopes, function: &parser::Function) -> Self {
+        // Type parameters and parameters get their own scope that wraps the body's scope so they
+        // can be shadowed, the type parameters are declared first since the signature uses them
+        scopes.new_scope();
+        let generics = function
+            .generics
+            .iter()
+            .map(|param| TypeParam {
+                name: scopes.new_type_param_id(&param.name),
+                span: param.span,
+            })
+            .collect();
+        let output = FunctionOutput::lower(scopes, &function.output);
+        let inputs = function
+            .inputs
+            .iter()
+            .map(|param| Param {
+                ty: Ty::lower(scopes, &param.ty),
+                name: scopes.new_id(&param.name.name),
+                span: param.span,
+            })
+            .collect();
+        let body = BlockExpr::lower(scopes, &function.body);
+        scopes.pop_scope();
+
+        Self {
+            name: function.name.name.clone(),
+            name_span: function.name.span,
+            generics,
+            inputs,
+            output,
+            body,
+            span: function.span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Operation {
+    pub name: Id,
+    pub inputs: Vec<Param>,
+    pub output: Ty,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Effect {
+    pub name: String,
+    pub operations: Vec<Operation>,
+    pub span: Span,
+}
+
+impl Effect {
+    /// Lowers an effect whose operations were already given ids, in the order they're declared in
+    fn lower(scopes: &mut Scopes, effect: &parser::Effect, operation_ids: Vec<Id>) -> Self {
+        let operations = operation_ids
+            .into_iter()
+            .zip(&effect.operations)
+            .map(|(name, operation)| {
+                scopes.new_scope();
+                let inputs = operation
+                    .inputs
+                    .iter()
+                    .map(|param| Param {
+                        ty: Ty::lower(scopes, &param.ty),
+                        name: scopes.new_id(&param.name.name),
+                        span: param.span,
+                    })
+                    .collect();
+                scopes.pop_scope();
+
+                Operation {
+                    name,
+                    inputs,
+                    output: Ty::lower(scopes, &operation.output),
+                    span: operation.span,
+                }
+            })
+            .collect();
+
+        Self {
+            name: effect.name.name.clone(),
+            operations,
+            span: effect.span,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
 pub enum ModuleItem {
-    Function(Function),
+    Function(Box<Function>),
+    Effect(Effect),
 }
 
 impl Hir {
-    pub fn lower(ast: &AST) -> Self {
-        let mut scopes = Scopes::with_prelude();
+    /// The operation an id refers to along with the id of its effect, if it refers to one
+    pub fn operation(&self, id: Id) -> Option<(Id, &Operation)> {
+        self.module.iter().find_map(|(effect_id, item)| match item {
+            ModuleItem::Effect(effect) => effect
+                .operations
+                .iter()
+                .find(|operation| operation.name == id)
+                .map(|operation| (*effect_id, operation)),
+            ModuleItem::Function(_) => None,
+        })
+    }
 
-        let module = ast
+    /// Lowers a file, along with the names in it that couldn't be resolved
+    ///
+    /// Lowering doesn't stop at those, they're replaced with placeholders like [Expr::Error] and
+    /// [Id::ERROR] which later passes skip over
+    pub fn lower(ast: &AST) -> (Self, Vec<Diagnostic>) {
+        let mut scopes = Scopes::with_std();
+
+        // Every item is in scope for every other item regardless of the order they were declared
+        // in, so we allocate all of their ids before lowering any of them
+        let ids = ast
             .module
             .iter()
             .map(|(name, item)| {
+                let kind = match item {
+                    parser::ModuleItem::Function(_) => Kind::Function,
+                    parser::ModuleItem::Effect(_) => Kind::Effect,
+                };
+                scopes.new_item_id(name, kind)
+            })
+            .collect::<Vec<_>>();
+        // Operations come after every item since items take precedence over them
+        let operation_ids = ids
+            .iter()
+            .zip(ast.module.values())
+            .map(|(id, item)| match item {
+                parser::ModuleItem::Function(_) => Vec::new(),
+                parser::ModuleItem::Effect(effect) => effect
+                    .operations
+                    .iter()
+                    .map(|operation| {
+                        scopes.new_operation_id((*id, &effect.name.name), &operation.name.name)
+                    })
+                    .collect(),
+            })
+            .collect::<Vec<_>>();
+
+        let module = ids
+            .into_iter()
+            .zip(operation_ids)
+            .zip(ast.module.iter())
+            .map(|((id, operation_ids), (name, item))| {
+                scopes.enter_item(&format!("crate::{name}"));
                 (
-                    scopes.new_id(name),
+                    id,
                     match item {
-                        parser::ModuleItem::Function(function) => ModuleItem::Function(Function {
-                            name: function.name.clone(),
-                            inputs: function.inputs,
-                            output: FunctionOutput::lower(&scopes, &function.output),
-                            body: BlockExpr::lower(&mut scopes, &function.body),
-                        }),
+                        parser::ModuleItem::Function(function) => {
+                            ModuleItem::Function(Box::new(Function::lower(&mut scopes, function)))
+                        }
+                        parser::ModuleItem::Effect(effect) => {
+                            ModuleItem::Effect(Effect::lower(&mut scopes, effect, operation_ids))
+                        }
                     },
                 )
             })
             .collect();
 
-        Self {
-            module,
-            id_map: scopes.into_id_map(),
-        }
+        let (id_map, diagnostics) = scopes.into_id_map();
+        (Self { module, id_map }, diagnostics)
     }
 }
@@ -1,11 +1,21 @@
-mod hir;
+pub mod call_graph;
+pub mod effects;
+pub mod hir;
+pub mod pretty;
+pub mod typeck;
 
 use hir::Hir;
 use petgraph::graphmap::DiGraphMap;
 
 fn get_function_calls(expr: &hir::Expr) -> Vec<&hir::FunctionCallExpr> {
     match expr {
-        hir::Expr::StringLiteral(_) => Vec::new(),
+        hir::Expr::StringLiteral(_)
+        | hir::Expr::IntLiteral(_)
+        | hir::Expr::FloatLiteral(_)
+        | hir::Expr::BoolLiteral(_)
+        | hir::Expr::Variable(_)
+        | hir::Expr::Unit(_)
+        | hir::Expr::Error(_) => Vec::new(),
         hir::Expr::FunctionCall(call) => {
             let mut vec = vec![call];
 
@@ -25,34 +35,57 @@ fn get_function_calls(expr: &hir::Expr) -> Vec<&hir::FunctionCallExpr> {
 
             vec
         }
+        hir::Expr::Block(block) => get_block_function_calls(block),
+        hir::Expr::If(if_expr) => get_function_calls(&if_expr.condition)
+            .into_iter()
+            .chain(get_block_function_calls(&if_expr.then_branch))
+            .chain(if_expr.else_branch.iter().flat_map(get_function_calls))
+            .collect(),
+        hir::Expr::Match(match_expr) => get_function_calls(&match_expr.scrutinee)
+            .into_iter()
+            .chain(
+                match_expr
+                    .arms
+                    .iter()
+                    .flat_map(|arm| get_function_calls(&arm.body)),
+            )
+            .collect(),
+        hir::Expr::Handle(handle) => get_block_function_calls(&handle.body)
+            .into_iter()
+            .chain(
+                handle
+                    .clauses
+                    .iter()
+                    .flat_map(|clause| get_function_calls(&clause.body)),
+            )
+            .collect(),
     }
 }
 
+fn get_block_function_calls(block: &hir::BlockExpr) -> Vec<&hir::FunctionCallExpr> {
+    block
+        .statements
+        .iter()
+        .flat_map(|statement| match statement {
+            hir::Statement::Let(let_statement) => get_function_calls(&let_statement.value),
+            hir::Statement::ExprStatement(expr) => get_function_calls(expr),
+        })
+        .chain(block.return_expression.iter().flat_map(get_function_calls))
+        .collect()
+}
+
 pub fn generate_call_graph(ast: &Hir) -> DiGraphMap<hir::Id, ()> {
     let mut graph = DiGraphMap::new();
 
     ast.module.iter().for_each(|(name, item)| {
-        let hir::ModuleItem::Function(function) = item;
+        let hir::ModuleItem::Function(function) = item else {
+            return;
+        };
 
         let function_node = graph.add_node(*name);
 
-        function
-            .body
-            .statements
-            .iter()
-            .flat_map(|statement| match statement {
-                hir::Statement::ExprStatement(expr) => get_function_calls(expr),
-            })
-            .for_each(|call| {
-                let node = graph.add_node(call.name);
-                graph.add_edge(function_node, node, ());
-            });
-
-        function
-            .body
-            .return_expression
-            .iter()
-            .flat_map(get_function_calls)
+        get_block_function_calls(&function.body)
+            .into_iter()
             .for_each(|call| {
                 let node = graph.add_node(call.name);
                 graph.add_edge(function_node, node, ());
@@ -64,11 +97,17 @@ pub fn generate_call_graph(ast: &Hir) -> DiGraphMap<hir::Id, ()> {
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::BTreeSet, sync::Arc};
+    use std::{
+        collections::{BTreeSet, HashSet},
+        sync::Arc,
+    };
 
     use parser::Parser;
 
-    use super::{generate_call_graph, hir::Hir};
+    use super::{
+        call_graph, generate_call_graph,
+        hir::{self, Hir},
+    };
 
     #[test]
     fn hello_world() {
@@ -90,7 +129,8 @@ fn main() -> Html {
             .into_output()
             .unwrap();
 
-        let hir = Hir::lower(&ast);
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
         let graph = generate_call_graph(&hir);
 
         let main = hir
@@ -113,4 +153,491 @@ fn main() -> Html {
                 .collect()
         );
     }
+
+    #[test]
+    fn functions_declared_after_their_callers() {
+        let parser = Parser::new();
+        let ast = parser
+            .parse(
+                r#"
+
+fn main() -> Html {
+    Html {
+        Body {
+            header,
+            footer
+        }
+    }
+}
+
+fn header() -> Html {
+    Paragraph("Welcome!")
+}
+
+fn footer() -> Html {
+    Paragraph("Goodbye!")
+}
+
+"#,
+            )
+            .into_output()
+            .unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+        let graph = generate_call_graph(&hir);
+
+        let names = hir
+            .module
+            .keys()
+            .map(|id| hir.id_map.get(id).unwrap().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["main", "header", "footer"].map(Arc::from));
+
+        let main = *hir.module.keys().next().unwrap();
+        let neighbors = graph
+            .neighbors(main)
+            .map(|neighbor| hir.id_map.get(&neighbor).unwrap().clone())
+            .collect::<BTreeSet<_>>();
+
+        assert_eq!(
+            neighbors,
+            ["Html", "Body", "header", "footer"]
+                .map(Arc::from)
+                .into_iter()
+                .collect()
+        );
+    }
+
+    #[test]
+    fn params_are_scoped_to_their_function() {
+        let source = r#"
+
+fn card(title: String) -> Html {
+    Paragraph(title)
+}
+
+fn main() -> Html {
+    Html {
+        card("Hello")
+    }
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let hir::ModuleItem::Function(card) = hir.module.values().next().unwrap() else {
+            panic!("expected only functions");
+        };
+        let [title] = &card.inputs[..] else {
+            panic!("expected card to have exactly one param");
+        };
+        assert_eq!(&**hir.id_map.get(&title.name).unwrap(), "title");
+        assert_eq!(&**hir.id_map.get(&title.ty.name).unwrap(), "String");
+
+        let Some(hir::Expr::FunctionCall(paragraph)) = &card.body.return_expression else {
+            panic!("expected card to return a Paragraph");
+        };
+        let [hir::Expr::Variable(arg)] = &paragraph.args[..] else {
+            panic!("expected Paragraph to be called with title");
+        };
+        assert_eq!(arg.name, title.name);
+        assert_eq!(&source[arg.span.start..arg.span.end], "title");
+        assert_eq!(&source[title.span.start..title.span.end], "title: String");
+    }
+
+    #[test]
+    fn let_shadows_earlier_bindings() {
+        let source = r#"
+
+fn main() -> Html {
+    let title = "Hello";
+    let title = title;
+    Html {
+        Paragraph(title)
+    }
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let hir::ModuleItem::Function(main) = hir.module.values().next().unwrap() else {
+            panic!("expected only functions");
+        };
+        let [hir::Statement::Let(first), hir::Statement::Let(second)] = &main.body.statements[..]
+        else {
+            panic!("expected main to start with two lets");
+        };
+        assert_ne!(first.name, second.name);
+
+        // The second let's value still refers to the first let
+        let hir::Expr::Variable(value) = &second.value else {
+            panic!("expected the second let to be bound to a variable");
+        };
+        assert_eq!(value.name, first.name);
+
+        let Some(hir::Expr::FunctionCall(html)) = &main.body.return_expression else {
+            panic!("expected main to return Html");
+        };
+        let [hir::Expr::FunctionCall(paragraph)] = &html.children[..] else {
+            panic!("expected Html to contain a Paragraph");
+        };
+        let [hir::Expr::Variable(arg)] = &paragraph.args[..] else {
+            panic!("expected Paragraph to be called with title");
+        };
+        assert_eq!(arg.name, second.name);
+    }
+
+    #[test]
+    fn match_arms_have_their_own_scopes() {
+        let source = r#"
+
+fn main() -> Html {
+    let count = 1;
+    match count {
+        0 => count,
+        count => count,
+    };
+    Html {}
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let hir::ModuleItem::Function(main) = hir.module.values().next().unwrap() else {
+            panic!("expected only functions");
+        };
+        let [hir::Statement::Let(count), hir::Statement::ExprStatement(hir::Expr::Match(match_expr))] =
+            &main.body.statements[..]
+        else {
+            panic!("expected main to start with a let and a match");
+        };
+        let [first, second] = &match_expr.arms[..] else {
+            panic!("expected the match to have two arms");
+        };
+
+        let hir::Expr::Variable(first_body) = &first.body else {
+            panic!("expected the first arm to return a variable");
+        };
+        assert_eq!(first_body.name, count.name);
+
+        // The binding only shadows the let inside of its own arm
+        let hir::Pattern::Binding(binding) = &second.pattern else {
+            panic!("expected the second arm to bind a name");
+        };
+        let hir::Expr::Variable(second_body) = &second.body else {
+            panic!("expected the second arm to return a variable");
+        };
+        assert_ne!(binding.name, count.name);
+        assert_eq!(second_body.name, binding.name);
+    }
+
+    #[test]
+    fn items_can_share_names_with_std() {
+        let source = r#"
+
+fn Paragraph() -> Html {
+    std::html::Paragraph("Hello")
+}
+
+fn main() -> Html {
+    Html {
+        Paragraph,
+        crate::Paragraph()
+    }
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let mut functions = hir.module.iter().map(|(id, item)| {
+            let hir::ModuleItem::Function(function) = item else {
+                panic!("expected only functions");
+            };
+            (*id, function)
+        });
+        let (paragraph_id, paragraph) = functions.next().unwrap();
+        let (_, main) = functions.next().unwrap();
+        assert_ne!(paragraph_id, hir::Id::PARAGRAPH);
+
+        let Some(hir::Expr::FunctionCall(from_std)) = &paragraph.body.return_expression else {
+            panic!("expected Paragraph to return a call");
+        };
+        assert_eq!(from_std.name, hir::Id::PARAGRAPH);
+
+        let Some(hir::Expr::FunctionCall(html)) = &main.body.return_expression else {
+            panic!("expected main to return Html");
+        };
+        let [hir::Expr::FunctionCall(unqualified), hir::Expr::FunctionCall(qualified)] =
+            &html.children[..]
+        else {
+            panic!("expected Html to contain two calls");
+        };
+        assert_eq!(unqualified.name, paragraph_id);
+        assert_eq!(qualified.name, paragraph_id);
+    }
+
+    #[test]
+    fn effect_rows_ignore_order() {
+        let source = r#"
+
+fn load() -> Html eff Console + Fetch {
+    Html {}
+}
+
+fn main() -> Html eff Fetch + std::console::Console + Fetch {
+    load()
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let mut functions = hir.module.values().map(|item| {
+            let hir::ModuleItem::Function(function) = item else {
+                panic!("expected only functions");
+            };
+            function
+        });
+        let load = functions.next().unwrap();
+        let main = functions.next().unwrap();
+
+        assert_eq!(load.output.effects, main.output.effects);
+        assert_eq!(main.output.effects.iter().count(), 2);
+        assert!(main.output.effects.contains(hir::Id::CONSOLE));
+        assert!(main.output.effects.contains(hir::Id::FETCH));
+    }
+
+    #[test]
+    fn operations_resolve_with_and_without_their_effect() {
+        let source = r#"
+
+effect Logger {
+    fn log(msg: String) -> ();
+}
+
+fn main() -> Html eff Console {
+    log("unqualified");
+    Logger::log("through the effect");
+    std::console::log("from std");
+    Html {}
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let mut items = hir.module.values();
+        let Some(hir::ModuleItem::Effect(logger)) = items.next() else {
+            panic!("expected Logger to be an effect");
+        };
+        let Some(hir::ModuleItem::Function(main)) = items.next() else {
+            panic!("expected main to be a function");
+        };
+        let [log] = &logger.operations[..] else {
+            panic!("expected Logger to have exactly one operation");
+        };
+
+        let names = main
+            .body
+            .statements
+            .iter()
+            .map(|statement| match statement {
+                hir::Statement::ExprStatement(hir::Expr::FunctionCall(call)) => call.name,
+                _ => panic!("expected main to only make calls"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(names, [log.name, log.name, hir::Id::LOG]);
+        assert!(hir.operation(log.name).is_some());
+    }
+
+    #[test]
+    fn handler_clauses_have_their_own_scopes() {
+        let source = r#"
+
+effect Logger {
+    fn log(msg: String) -> String;
+}
+
+fn main() -> Html {
+    let msg = "outer";
+    handle {
+        Logger::log(msg);
+    } with Logger {
+        log(msg) => resume(msg),
+    }
+    Html {}
+}
+
+"#;
+        let parser = Parser::new();
+        let ast = parser.parse(source).into_output().unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+
+        let Some(hir::ModuleItem::Function(main)) = hir.module.values().nth(1) else {
+            panic!("expected main to be a function");
+        };
+        let [hir::Statement::Let(outer), hir::Statement::ExprStatement(hir::Expr::Handle(handle))] =
+            &main.body.statements[..]
+        else {
+            panic!("expected main to start with a let and a handle");
+        };
+        let [clause] = &handle.clauses[..] else {
+            panic!("expected the handle to have exactly one clause");
+        };
+        let [param] = &clause.params[..] else {
+            panic!("expected the clause to bind exactly one param");
+        };
+        assert_ne!(param.name, outer.name);
+        assert!(hir.operation(clause.operation).is_some());
+
+        let hir::Expr::FunctionCall(resume) = &clause.body else {
+            panic!("expected the clause to resume");
+        };
+        let [hir::Expr::Variable(arg)] = &resume.args[..] else {
+            panic!("expected resume to be called with msg");
+        };
+        assert_eq!(resume.name, clause.resume);
+        assert_eq!(arg.name, param.name);
+    }
+
+    #[test]
+    fn lowering_is_deterministic() {
+        let parser = Parser::new();
+        let ast = parser
+            .parse(
+                r#"
+
+effect Logger {
+    fn log(message: String) -> ();
+}
+
+fn greet(name: String) -> String eff Logger {
+    let name = name;
+    Logger::log(name);
+    name
+}
+
+fn main() -> Html {
+    let name = handle {
+        greet("Ferris")
+    } with Logger {
+        log(message) => resume(()),
+    };
+    Html {
+        Body {
+            Paragraph(name)
+        }
+    }
+}
+
+"#,
+            )
+            .into_output()
+            .unwrap();
+
+        let (first, _) = Hir::lower(&ast);
+        let (second, _) = Hir::lower(&ast);
+        assert_eq!(first, second);
+
+        let greet = first
+            .module
+            .keys()
+            .copied()
+            .find(|id| &*first.id_map[id] == "greet")
+            .expect("greet to be lowered");
+        assert_eq!(greet, hir::Id::from_path("crate::greet"));
+        assert_eq!(hir::Id::HTML, hir::Id::from_path("std::html::Html"));
+    }
+
+    #[test]
+    fn call_graph_analyses() {
+        let parser = Parser::new();
+        let ast = parser
+            .parse(
+                r#"
+
+effect Logger {
+    fn log(message: String) -> ();
+}
+
+fn countdown(n: Int) -> Int {
+    match n {
+        0 => 0,
+        _ => countdown(n),
+    }
+}
+
+fn ping(n: Int) -> Int {
+    pong(n)
+}
+
+fn pong(n: Int) -> Int {
+    ping(n)
+}
+
+fn shout(message: String) -> String {
+    message
+}
+
+fn main() -> Html {
+    let count = {
+        let n = 3;
+        countdown(n)
+    };
+    let greeting = handle {
+        Logger::log("Hello");
+        "done"
+    } with Logger {
+        log(message) => resume(if true { shout(message); () } else { () }),
+    };
+    Html {}
+}
+
+"#,
+            )
+            .into_output()
+            .unwrap();
+
+        let (hir, diagnostics) = Hir::lower(&ast);
+        assert_eq!(diagnostics, Vec::new());
+        let graph = generate_call_graph(&hir);
+        let id = |name: &str| hir::Id::from_path(&format!("crate::{name}"));
+
+        let reachable = call_graph::reachable(&hir, &graph);
+        assert_eq!(
+            reachable,
+            HashSet::from([id("main"), id("countdown"), id("shout")])
+        );
+
+        let recursive = call_graph::recursive(&hir, &graph);
+        assert_eq!(
+            recursive,
+            vec![vec![id("countdown")], vec![id("ping"), id("pong")]]
+        );
+    }
 }
@@ -0,0 +1,269 @@
+//! Prints the HIR back out as code, which is how it's inspected while working on the compiler
+//!
+//! Every id is printed as the name it was given in the source, so locals that shadow each other
+//! print the same even though the HIR tells them apart
+
+use std::fmt::Write;
+
+use crate::hir::{self, Hir, Id};
+
+const INDENT: &str = "    ";
+
+struct Printer<'a> {
+    hir: &'a Hir,
+    out: String,
+    depth: usize,
+}
+
+impl<'a> Printer<'a> {
+    fn name(&self, id: Id) -> &'a str {
+        if id == Id::ERROR {
+            return "{error}";
+        }
+        self.hir.id_map.get(&id).map_or("{unknown}", |name| name)
+    }
+
+    fn newline(&mut self) {
+        self.out.push('\n');
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    /// Prints each item after its own newline one level deeper, followed by a newline back at the
+    /// current level
+    fn indented<T>(&mut self, items: &[T], mut print: impl FnMut(&mut Self, &T)) {
+        self.depth += 1;
+        for item in items {
+            self.newline();
+            print(self, item);
+        }
+        self.depth -= 1;
+        self.newline();
+    }
+
+    fn separated<T>(&mut self, items: &[T], separator: &str, mut print: impl FnMut(&mut Self, &T)) {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(separator);
+            }
+            print(self, item);
+        }
+    }
+
+    fn ty(&mut self, ty: &hir::Ty) {
+        if ty.name == Id::UNIT {
+            self.out.push_str("()");
+            return;
+        }
+
+        self.out.push_str(self.name(ty.name));
+        if !ty.args.is_empty() {
+            self.out.push('<');
+            self.separated(&ty.args, ", ", Self::ty);
+            self.out.push('>');
+        }
+    }
+
+    fn params(&mut self, params: &[hir::Param]) {
+        self.out.push('(');
+        self.separated(params, ", ", |printer, param| {
+            printer.out.push_str(printer.name(param.name));
+            printer.out.push_str(": ");
+            printer.ty(&param.ty);
+        });
+        self.out.push(')');
+    }
+
+    fn function(&mut self, function: &hir::Function) {
+        write!(self.out, "fn {}", function.name).unwrap();
+        if !function.generics.is_empty() {
+            self.out.push('<');
+            self.separated(&function.generics, ", ", |printer, param| {
+                printer.out.push_str(printer.name(param.name))
+            });
+            self.out.push('>');
+        }
+        self.params(&function.inputs);
+        self.out.push_str(" -> ");
+        self.ty(&function.output.ty);
+
+        let effects = function
+            .output
+            .effects
+            .iter()
+            .map(|(effect, _)| self.name(effect))
+            .collect::<Vec<_>>();
+        if !effects.is_empty() {
+            write!(self.out, " eff {}", effects.join(" + ")).unwrap();
+        }
+        self.out.push(' ');
+        self.block(&function.body);
+    }
+
+    fn effect(&mut self, effect: &hir::Effect) {
+        write!(self.out, "effect {} {{", effect.name).unwrap();
+        self.indented(&effect.operations, |printer, operation| {
+            write!(printer.out, "fn {}", printer.name(operation.name)).unwrap();
+            printer.params(&operation.inputs);
+            printer.out.push_str(" -> ");
+            printer.ty(&operation.output);
+            printer.out.push(';');
+        });
+        self.out.push('}');
+    }
+
+    fn block(&mut self, block: &hir::BlockExpr) {
+        self.out.push('{');
+        let lines = block
+            .statements
+            .iter()
+            .map(Line::Statement)
+            .chain(block.return_expression.iter().map(Line::Return))
+            .collect::<Vec<_>>();
+        if lines.is_empty() {
+            self.out.push('}');
+            return;
+        }
+
+        self.indented(&lines, |printer, line| match line {
+            Line::Statement(hir::Statement::Let(let_statement)) => {
+                write!(printer.out, "let {}", printer.name(let_statement.name)).unwrap();
+                if let Some(ty) = &let_statement.ty {
+                    printer.out.push_str(": ");
+                    printer.ty(ty);
+                }
+                printer.out.push_str(" = ");
+                printer.expr(&let_statement.value);
+                printer.out.push(';');
+            }
+            Line::Statement(hir::Statement::ExprStatement(expr)) => {
+                printer.expr(expr);
+                printer.out.push(';');
+            }
+            Line::Return(expr) => printer.expr(expr),
+        });
+        self.out.push('}');
+    }
+
+    fn expr(&mut self, expr: &hir::Expr) {
+        match expr {
+            hir::Expr::StringLiteral(string) => write!(self.out, "{:?}", string.value).unwrap(),
+            hir::Expr::IntLiteral(int) => write!(self.out, "{}", int.value).unwrap(),
+            hir::Expr::FloatLiteral(float) => write!(self.out, "{:?}", float.value).unwrap(),
+            hir::Expr::BoolLiteral(bool) => write!(self.out, "{}", bool.value).unwrap(),
+            hir::Expr::Variable(variable) => self.out.push_str(self.name(variable.name)),
+            hir::Expr::FunctionCall(call) => {
+                // Operations are called through their effect so they can't be mistaken for
+                // anything else that shares their name
+                if let Some((effect, _)) = self.hir.operation(call.name) {
+                    write!(self.out, "{}::", self.name(effect)).unwrap();
+                }
+                self.out.push_str(self.name(call.name));
+                // Constructors without fields are written on their own, like they are in patterns
+                let bare = matches!(hir::std_kind(call.name), Some(hir::Kind::Constructor(_)));
+                if !call.args.is_empty() || call.children.is_empty() && !bare {
+                    self.out.push('(');
+                    self.separated(&call.args, ", ", Self::expr);
+                    self.out.push(')');
+                }
+                if !call.children.is_empty() {
+                    self.out.push_str(" {");
+                    self.indented(&call.children, |printer, child| {
+                        printer.expr(child);
+                        printer.out.push(',');
+                    });
+                    self.out.push('}');
+                }
+            }
+            hir::Expr::Block(block) => self.block(block),
+            hir::Expr::If(if_expr) => {
+                self.out.push_str("if ");
+                self.expr(&if_expr.condition);
+                self.out.push(' ');
+                self.block(&if_expr.then_branch);
+                if let Some(else_branch) = &if_expr.else_branch {
+                    self.out.push_str(" else ");
+                    self.expr(else_branch);
+                }
+            }
+            hir::Expr::Match(match_expr) => {
+                self.out.push_str("match ");
+                self.expr(&match_expr.scrutinee);
+                self.out.push_str(" {");
+                self.indented(&match_expr.arms, |printer, arm| {
+                    printer.pattern(&arm.pattern);
+                    printer.out.push_str(" => ");
+                    printer.expr(&arm.body);
+                    printer.out.push(',');
+                });
+                self.out.push('}');
+            }
+            hir::Expr::Handle(handle) => {
+                self.out.push_str("handle ");
+                self.block(&handle.body);
+                write!(self.out, " with {} {{", self.name(handle.effect)).unwrap();
+                self.indented(&handle.clauses, |printer, clause| {
+                    printer.out.push_str(printer.name(clause.operation));
+                    printer.out.push('(');
+                    printer.separated(&clause.params, ", ", |printer, param| {
+                        printer.out.push_str(printer.name(param.name))
+                    });
+                    printer.out.push_str(") => ");
+                    printer.expr(&clause.body);
+                    printer.out.push(',');
+                });
+                self.out.push('}');
+            }
+            hir::Expr::Unit(_) => self.out.push_str("()"),
+            hir::Expr::Error(_) => self.out.push_str("{error}"),
+        }
+    }
+
+    fn pattern(&mut self, pattern: &hir::Pattern) {
+        match pattern {
+            hir::Pattern::Wildcard(_) => self.out.push('_'),
+            hir::Pattern::Binding(binding) => self.out.push_str(self.name(binding.name)),
+            hir::Pattern::StringLiteral(string) => write!(self.out, "{:?}", string.value).unwrap(),
+            hir::Pattern::IntLiteral(int) => write!(self.out, "{}", int.value).unwrap(),
+            hir::Pattern::BoolLiteral(bool) => write!(self.out, "{}", bool.value).unwrap(),
+            hir::Pattern::Constructor(constructor) => {
+                self.out.push_str(self.name(constructor.name));
+                if !constructor.fields.is_empty() {
+                    self.out.push('(');
+                    self.separated(&constructor.fields, ", ", Self::pattern);
+                    self.out.push(')');
+                }
+            }
+            hir::Pattern::Error(_) => self.out.push_str("{error}"),
+        }
+    }
+}
+
+/// A line of a block, which is either one of its statements or the expression it returns
+enum Line<'a> {
+    Statement(&'a hir::Statement),
+    Return(&'a hir::Expr),
+}
+
+/// Prints every item of the HIR in the order they were declared in, separated by blank lines
+pub fn print(hir: &Hir) -> String {
+    let mut printer = Printer {
+        hir,
+        out: String::new(),
+        depth: 0,
+    };
+
+    for (i, item) in hir.module.values().enumerate() {
+        if i > 0 {
+            printer.out.push_str("\n\n");
+        }
+        match item {
+            hir::ModuleItem::Function(function) => printer.function(function),
+            hir::ModuleItem::Effect(effect) => printer.effect(effect),
+        }
+    }
+    printer.out.push('\n');
+
+    printer.out
+}
@@ -0,0 +1,1305 @@
+use std::collections::{HashMap, HashSet};
+
+use diagnostics::{codes, Diagnostic};
+use parser::Span;
+
+use crate::hir::{self, Hir, Id, Kind};
+
+/// A type constructor along with the types it's given, as in `String` which is given none or
+/// `List<String>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Type {
+    name: Id,
+    args: Vec<Type>,
+}
+
+impl Type {
+    /// A type that takes no type arguments
+    fn of(name: Id) -> Self {
+        Type {
+            name,
+            args: Vec::new(),
+        }
+    }
+
+    fn is(&self, name: Id) -> bool {
+        self.name == name && self.args.is_empty()
+    }
+}
+
+struct TypeChecker<'a> {
+    hir: &'a Hir,
+    /// The types of the parameters and variables seen so far, ids are unique so there's no need to
+    /// track which scope they belong to
+    locals: HashMap<Id, Type>,
+    /// The type parameters of every function, which are unique to their function like locals
+    type_params: HashSet<Id>,
+    /// The type each `resume` in scope takes and the type it returns, which are the output of the
+    /// handled operation and the type of the handled block
+    resumes: HashMap<Id, (Option<Type>, Option<Type>)>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// The types a call to a function or an operation takes and the type it returns
+struct Signature {
+    /// The type of each parameter along with where it was declared, neither of which are known
+    /// for std
+    params: Vec<(Option<Type>, Option<Span>)>,
+    output: Option<Type>,
+}
+
+impl<'a> TypeChecker<'a> {
+    fn name(&self, id: Id) -> &'a str {
+        self.hir.id_map.get(&id).map_or("{unknown}", |name| name)
+    }
+
+    /// How a type is written out, as in `List<String>`
+    fn display(&self, ty: &Type) -> String {
+        if ty.args.is_empty() {
+            return self.name(ty.name).to_string();
+        }
+
+        let args = ty
+            .args
+            .iter()
+            .map(|arg| self.display(arg))
+            .collect::<Vec<_>>();
+        format!("{}<{}>", self.name(ty.name), args.join(", "))
+    }
+
+    fn kind(&self, id: Id) -> Kind {
+        if self.type_params.contains(&id) {
+            return Kind::Type(0);
+        }
+
+        match self.hir.module.get(&id) {
+            Some(hir::ModuleItem::Function(_)) => Kind::Function,
+            Some(hir::ModuleItem::Effect(_)) => Kind::Effect,
+            None => match self.hir.operation(id) {
+                Some((effect, _)) => Kind::Operation(effect),
+                None => hir::std_kind(id).unwrap_or(Kind::Local),
+            },
+        }
+    }
+
+    /// The type a type annotation stands for
+    ///
+    /// Inside of a generic function its type parameters are types of their own that nothing else
+    /// is, they're only replaced by the types of the args where the function is called
+    ///
+    /// Annotations that aren't types at all or that give a type the wrong number of type arguments
+    /// are `None`, which `check_ty` reports
+    fn known_ty(&self, ty: &hir::Ty) -> Option<Type> {
+        match self.kind(ty.name) {
+            Kind::Type(params) if params == ty.args.len() => Some(Type {
+                name: ty.name,
+                args: ty
+                    .args
+                    .iter()
+                    .map(|arg| self.known_ty(arg))
+                    .collect::<Option<_>>()?,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Checks that a type annotation only names types and gives each of them as many type
+    /// arguments as they take
+    fn check_ty(&mut self, ty: &hir::Ty) {
+        if ty.name == Id::ERROR {
+            return;
+        }
+
+        match self.kind(ty.name) {
+            Kind::Type(params) => {
+                if params != ty.args.len() {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            codes::WRONG_NUMBER_OF_TYPE_ARGUMENTS,
+                            ty.span,
+                            format!(
+                                "`{}` takes {} but {} supplied",
+                                self.name(ty.name),
+                                type_arguments(params),
+                                match ty.args.len() {
+                                    0 => "none were".to_string(),
+                                    1 => "1 was".to_string(),
+                                    supplied => format!("{supplied} were"),
+                                }
+                            ),
+                        )
+                        .with_label(format!("expected {}", type_arguments(params))),
+                    );
+                }
+
+                for arg in &ty.args {
+                    self.check_ty(arg);
+                }
+            }
+            kind => self.diagnostics.push(
+                Diagnostic::error(
+                    codes::NOT_A_TYPE,
+                    ty.span,
+                    format!(
+                        "expected type, found {} `{}`",
+                        kind.describe(),
+                        self.name(ty.name)
+                    ),
+                )
+                .with_label("not a type"),
+            ),
+        }
+    }
+
+    fn check_effects(&mut self, effects: &hir::EffectRow) {
+        for (effect, span) in effects.iter() {
+            match self.kind(effect) {
+                Kind::Effect => {}
+                kind => self.diagnostics.push(
+                    Diagnostic::error(
+                        codes::NOT_AN_EFFECT,
+                        span,
+                        format!(
+                            "expected effect, found {} `{}`",
+                            kind.describe(),
+                            self.name(effect)
+                        ),
+                    )
+                    .with_label("not an effect"),
+                ),
+            }
+        }
+    }
+
+    /// Infers the type of a block, checking everything inside of it along the way, blocks without
+    /// a return expression are `()`
+    ///
+    /// Types that can't be known, such as those of expressions that failed to parse, are `None`
+    /// and never cause an error
+    fn check_block(&mut self, block: &hir::BlockExpr) -> Option<Type> {
+        for statement in &block.statements {
+            match statement {
+                hir::Statement::Let(let_statement) => {
+                    let value = self.check_expr(&let_statement.value);
+                    let annotation = let_statement.ty.as_ref().map(|ty| {
+                        self.check_ty(ty);
+                        self.known_ty(ty)
+                    });
+                    if let (Some(ty), Some(Some(expected)), Some(found)) =
+                        (&let_statement.ty, &annotation, &value)
+                    {
+                        if expected != found {
+                            self.diagnostics.push(
+                                Diagnostic::error(
+                                    codes::MISMATCHED_TYPES,
+                                    let_statement.value.span(),
+                                    "mismatched types",
+                                )
+                                .with_label(format!(
+                                    "expected `{}`, found `{}`",
+                                    self.display(expected),
+                                    self.display(found)
+                                ))
+                                .with_secondary(ty.span, "expected because of this annotation"),
+                            );
+                        }
+                    }
+                    if let Some(ty) = annotation.unwrap_or(value) {
+                        self.locals.insert(let_statement.name, ty);
+                    }
+                }
+                hir::Statement::ExprStatement(expr) => {
+                    self.check_expr(expr);
+                }
+            }
+        }
+
+        match &block.return_expression {
+            Some(expr) => self.check_expr(expr),
+            None => Some(Type::of(Id::UNIT)),
+        }
+    }
+
+    /// Checks that a function's body evaluates to the type it says it returns
+    fn check_output(&mut self, function: &hir::Function) {
+        let found = self.check_block(&function.body);
+        let Some(expected) = self.known_ty(&function.output.ty) else {
+            return;
+        };
+        if function.name == "main" && !expected.is(Id::HTML) {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::MISMATCHED_TYPES,
+                    function.output.ty.span,
+                    "`main` must return `Html`",
+                )
+                .with_label(format!(
+                    "expected `Html`, found `{}`",
+                    self.display(&expected)
+                ))
+                .with_note("what `main` returns is rendered as the page"),
+            );
+            return;
+        }
+
+        match found {
+            Some(found) if found != expected => {
+                let span = match &function.body.return_expression {
+                    Some(expr) => branch_span(expr),
+                    // Points at the closing brace, which is where the missing value would go
+                    None => Span::new(function.body.span.end - 1, function.body.span.end),
+                };
+                self.diagnostics.push(
+                    Diagnostic::error(codes::MISMATCHED_TYPES, span, "mismatched types")
+                        .with_label(format!(
+                            "expected `{}`, found `{}`",
+                            self.display(&expected),
+                            self.display(&found)
+                        ))
+                        .with_secondary(
+                            function.output.ty.span,
+                            format!("`{}` returns `{}`", function.name, self.display(&expected)),
+                        ),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn check_expr(&mut self, expr: &hir::Expr) -> Option<Type> {
+        match expr {
+            hir::Expr::StringLiteral(_) => Some(Type::of(Id::STRING)),
+            hir::Expr::IntLiteral(_) => Some(Type::of(Id::INT)),
+            hir::Expr::FloatLiteral(_) => Some(Type::of(Id::FLOAT)),
+            hir::Expr::BoolLiteral(_) => Some(Type::of(Id::BOOL)),
+            hir::Expr::Variable(variable) => self.locals.get(&variable.name).cloned(),
+            hir::Expr::FunctionCall(call) => {
+                let args = call
+                    .args
+                    .iter()
+                    .map(|expr| self.check_expr(expr))
+                    .collect::<Vec<_>>();
+                call.children.iter().for_each(|expr| {
+                    self.check_child(expr);
+                });
+                self.check_children_allowed(call);
+
+                if let Some((input, output)) = self.resumes.get(&call.name).cloned() {
+                    self.check_args(call, &[(input, None)], &args);
+                    return output;
+                }
+
+                if let Kind::Constructor(of) = self.kind(call.name) {
+                    let fields = hir::std_fields(call.name).unwrap_or_default();
+                    self.check_args(call, &vec![(None, None); fields.len()], &args);
+                    return self.constructed(of, fields, &args);
+                }
+
+                if let Some(signature) = self.operation_signature(call.name) {
+                    self.check_args(call, &signature.params, &args);
+                    return signature.output;
+                }
+
+                match self.hir.module.get(&call.name) {
+                    Some(hir::ModuleItem::Function(function)) => {
+                        let substitutions = self.infer_type_params(function, &args);
+                        let substitute = |ty: Option<Type>| {
+                            ty.and_then(|ty| substitute(&ty, &function.generics, &substitutions))
+                        };
+
+                        let params = function
+                            .inputs
+                            .iter()
+                            .map(|param| (substitute(self.known_ty(&param.ty)), Some(param.span)))
+                            .collect::<Vec<_>>();
+                        self.check_args(call, &params, &args);
+                        substitute(self.known_ty(&function.output.ty))
+                    }
+                    // Elements that contain other elements take them as children instead
+                    None if [Id::HTML, Id::BODY].contains(&call.name) => {
+                        self.check_args(call, &[], &args);
+                        Some(Type::of(Id::HTML))
+                    }
+                    None if call.name == Id::PARAGRAPH => {
+                        self.check_args(call, &[(None, None)], &args);
+                        if let ([arg], [Some(ty)]) = (&call.args[..], &args[..]) {
+                            self.check_text(arg, ty);
+                        }
+                        Some(Type::of(Id::HTML))
+                    }
+                    Some(hir::ModuleItem::Effect(_)) | None => None,
+                }
+            }
+            hir::Expr::Block(block) => self.check_block(block),
+            hir::Expr::If(if_expr) => self.check_if(if_expr, false),
+            hir::Expr::Match(match_expr) => self.check_match(match_expr),
+            hir::Expr::Handle(handle) => self.check_handle(handle),
+            hir::Expr::Unit(_) => Some(Type::of(Id::UNIT)),
+            hir::Expr::Error(_) => None,
+        }
+    }
+
+    /// The type of a value made with a constructor of `ty` from the types of its fields, `None`
+    /// when one of the type arguments isn't held by any of them, as for `None`
+    fn constructed(&self, ty: Id, fields: &[usize], args: &[Option<Type>]) -> Option<Type> {
+        let Kind::Type(params) = self.kind(ty) else {
+            return None;
+        };
+
+        Some(Type {
+            name: ty,
+            args: (0..params)
+                .map(|param| {
+                    let field = fields.iter().position(|&field| field == param)?;
+                    args.get(field).cloned().flatten()
+                })
+                .collect::<Option<_>>()?,
+        })
+    }
+
+    /// Checks that what's given to an element to show as its text is something that can be written
+    /// out as text
+    fn check_text(&mut self, arg: &hir::Expr, ty: &Type) {
+        if [Id::STRING, Id::INT, Id::FLOAT, Id::BOOL]
+            .iter()
+            .any(|&text| ty.is(text))
+        {
+            return;
+        }
+
+        self.diagnostics.push(
+            Diagnostic::error(codes::MISMATCHED_TYPES, arg.span(), "mismatched types")
+                .with_label(format!(
+                    "expected `String`, `Int`, `Float` or `Bool`, found `{}`",
+                    self.display(ty)
+                ))
+                .with_note("`Paragraph` only contains text"),
+        );
+    }
+
+    /// Checks that only the elements that contain other elements are given children, they'd be
+    /// left out of anything else
+    fn check_children_allowed(&mut self, call: &hir::FunctionCallExpr) {
+        let (Some(first), Some(last)) = (call.children.first(), call.children.last()) else {
+            return;
+        };
+        if [Id::HTML, Id::BODY].contains(&call.name) {
+            return;
+        }
+
+        let diagnostic = Diagnostic::error(
+            codes::WRONG_NUMBER_OF_ARGUMENTS,
+            Span::new(first.span().start, last.span().end),
+            format!("`{}` doesn't take children", self.name(call.name)),
+        )
+        .with_label("children aren't allowed here");
+        self.diagnostics.push(match call.name {
+            Id::PARAGRAPH => diagnostic.with_help("pass what goes in it as an argument instead"),
+            _ => diagnostic,
+        });
+    }
+
+    /// The type each type parameter of a function stands for in a call to it, which is decided by
+    /// the first arg passed for a parameter whose type has it, as `T` is `Int` for a `List<T>`
+    /// given a `List<Int>`
+    ///
+    /// Type parameters that none of the args decide are left out, they can't be known
+    fn infer_type_params(
+        &self,
+        function: &hir::Function,
+        args: &[Option<Type>],
+    ) -> HashMap<Id, Type> {
+        let mut substitutions = HashMap::new();
+        for (param, arg) in function.inputs.iter().zip(args) {
+            if let (Some(ty), Some(arg)) = (self.known_ty(&param.ty), arg) {
+                infer(&ty, arg, &function.generics, &mut substitutions);
+            }
+        }
+        substitutions
+    }
+
+    /// The signature of an operation, `None` if what's being called isn't an operation
+    fn operation_signature(&self, id: Id) -> Option<Signature> {
+        if let Some((_, operation)) = self.hir.operation(id) {
+            return Some(Signature {
+                params: operation
+                    .inputs
+                    .iter()
+                    .map(|param| (self.known_ty(&param.ty), Some(param.span)))
+                    .collect(),
+                output: self.known_ty(&operation.output),
+            });
+        }
+
+        hir::std_signature(id).map(|(inputs, output)| Signature {
+            params: inputs
+                .iter()
+                .map(|&input| (Some(Type::of(input)), None))
+                .collect(),
+            output: Some(Type::of(output)),
+        })
+    }
+
+    fn check_handle(&mut self, handle: &hir::HandleExpr) -> Option<Type> {
+        let body_ty = self.check_block(&handle.body);
+
+        let operations = match self.kind(handle.effect) {
+            Kind::Effect => match self.hir.module.get(&handle.effect) {
+                Some(hir::ModuleItem::Effect(effect)) => effect
+                    .operations
+                    .iter()
+                    .map(|operation| operation.name)
+                    .collect(),
+                _ => hir::std_operations(handle.effect).collect(),
+            },
+            kind => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        codes::NOT_AN_EFFECT,
+                        handle.effect_span,
+                        format!(
+                            "expected effect, found {} `{}`",
+                            kind.describe(),
+                            self.name(handle.effect)
+                        ),
+                    )
+                    .with_label("not an effect"),
+                );
+                Vec::new()
+            }
+        };
+
+        let missing = operations
+            .iter()
+            .filter(|&&operation| {
+                !handle
+                    .clauses
+                    .iter()
+                    .any(|clause| clause.operation == operation)
+            })
+            .map(|&operation| format!("`{}`", self.name(operation)))
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::INCOMPLETE_HANDLER,
+                    handle.effect_span,
+                    format!(
+                        "not every operation of `{}` is handled",
+                        self.name(handle.effect)
+                    ),
+                )
+                .with_label(format!("missing {}", missing.join(", ")))
+                .with_help("add a clause for each of them after `with`"),
+            );
+        }
+
+        for clause in &handle.clauses {
+            let Some(signature) = self.operation_signature(clause.operation) else {
+                self.check_expr(&clause.body);
+                continue;
+            };
+
+            if signature.params.len() != clause.params.len() {
+                let plural = |count| if count == 1 { "" } else { "s" };
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        codes::WRONG_NUMBER_OF_ARGUMENTS,
+                        clause.span,
+                        format!(
+                            "`{}` takes {} argument{} but its clause binds {}",
+                            self.name(clause.operation),
+                            signature.params.len(),
+                            plural(signature.params.len()),
+                            clause.params.len(),
+                        ),
+                    )
+                    .with_label(format!(
+                        "expected {} parameter{}",
+                        signature.params.len(),
+                        plural(signature.params.len())
+                    )),
+                );
+            }
+            for (param, (ty, _)) in clause.params.iter().zip(&signature.params) {
+                if let Some(ty) = ty {
+                    self.locals.insert(param.name, ty.clone());
+                }
+            }
+            self.resumes
+                .insert(clause.resume, (signature.output, body_ty.clone()));
+
+            match (&body_ty, self.check_expr(&clause.body)) {
+                (Some(expected), Some(found)) if *expected != found => self.diagnostics.push(
+                    Diagnostic::error(
+                        codes::MISMATCHED_TYPES,
+                        branch_span(&clause.body),
+                        "mismatched types",
+                    )
+                    .with_label(format!(
+                        "expected `{}`, found `{}`",
+                        self.display(expected),
+                        self.display(&found)
+                    ))
+                    .with_secondary(
+                        block_value_span(&handle.body),
+                        format!("the handled block is `{}`", self.display(expected)),
+                    ),
+                ),
+                _ => {}
+            }
+        }
+
+        body_ty
+    }
+
+    /// Checks that a call passes an argument of the right type for each of the parameters
+    fn check_args(
+        &mut self,
+        call: &hir::FunctionCallExpr,
+        params: &[(Option<Type>, Option<Span>)],
+        args: &[Option<Type>],
+    ) {
+        if params.len() != args.len() {
+            let plural = |count| if count == 1 { "" } else { "s" };
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::WRONG_NUMBER_OF_ARGUMENTS,
+                    call.span,
+                    format!(
+                        "`{}` takes {} argument{} but {} {} supplied",
+                        self.name(call.name),
+                        params.len(),
+                        plural(params.len()),
+                        args.len(),
+                        if args.len() == 1 { "was" } else { "were" }
+                    ),
+                )
+                .with_label(format!(
+                    "expected {} argument{}",
+                    params.len(),
+                    plural(params.len())
+                )),
+            );
+            return;
+        }
+
+        for ((param_ty, param_span), (arg_ty, arg)) in
+            params.iter().zip(args.iter().zip(&call.args))
+        {
+            match (param_ty, arg_ty) {
+                (Some(expected), Some(found)) if expected != found => {
+                    let mut diagnostic =
+                        Diagnostic::error(codes::MISMATCHED_TYPES, arg.span(), "mismatched types")
+                            .with_label(format!(
+                                "expected `{}`, found `{}`",
+                                self.display(expected),
+                                self.display(found)
+                            ));
+                    if let Some(span) = *param_span {
+                        diagnostic = diagnostic.with_secondary(
+                            span,
+                            format!("parameter of `{}`", self.name(call.name)),
+                        );
+                    }
+                    self.diagnostics.push(diagnostic);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Checks that `main` can be called without anything, since it's the page itself that calls it
+    fn check_main_signature(&mut self, function: &hir::Function) {
+        let spans = [
+            (
+                "type parameters",
+                function
+                    .generics
+                    .iter()
+                    .map(|param| param.span)
+                    .collect::<Vec<_>>(),
+            ),
+            (
+                "parameters",
+                function.inputs.iter().map(|param| param.span).collect(),
+            ),
+        ];
+        for (what, spans) in spans {
+            let (Some(first), Some(last)) = (spans.first(), spans.last()) else {
+                continue;
+            };
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::INVALID_MAIN,
+                    Span::new(first.start, last.end),
+                    format!("`main` can't take {what}"),
+                )
+                .with_label(format!("`main` takes {what} here"))
+                .with_note("`main` is called by the page, which has nothing to pass to it"),
+            );
+        }
+    }
+
+    /// Checks that `main` only performs effects the page knows how to perform, which is only those
+    /// from std for now
+    fn check_main_effects(&mut self, effects: &hir::EffectRow) {
+        for (effect, span) in effects.iter() {
+            if let Kind::Effect = self.kind(effect) {
+                if hir::std_kind(effect).is_none() {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            codes::UNHANDLED_EFFECT,
+                            span,
+                            format!("`main` can't perform the `{}` effect", self.name(effect)),
+                        )
+                        .with_label("nothing handles this effect")
+                        .with_note("the page itself only knows how to perform effects from std")
+                        .with_help("handle it inside of `main` with `handle`"),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks a child of an element, which unlike other expressions can be an `if` without an
+    /// `else` as a child that's left out when its condition is false
+    fn check_child(&mut self, child: &hir::Expr) -> Option<Type> {
+        match child {
+            hir::Expr::If(if_expr) => self.check_if(if_expr, true),
+            child => self.check_expr(child),
+        }
+    }
+
+    /// Infers the type of an `if`, where one without an `else` is `()` unless it's the child of an
+    /// element
+    fn check_if(&mut self, if_expr: &hir::IfExpr, is_child: bool) -> Option<Type> {
+        match self.check_expr(&if_expr.condition) {
+            Some(ty) if !ty.is(Id::BOOL) => self.diagnostics.push(
+                Diagnostic::error(
+                    codes::MISMATCHED_TYPES,
+                    if_expr.condition.span(),
+                    "mismatched types",
+                )
+                .with_label(format!("expected `Bool`, found `{}`", self.display(&ty)))
+                .with_note("the condition of an `if` must be a `Bool`"),
+            ),
+            _ => {}
+        }
+
+        let then_ty = self.check_block(&if_expr.then_branch);
+
+        let Some(else_branch) = &if_expr.else_branch else {
+            if is_child {
+                return then_ty;
+            }
+
+            // Without an `else` there's no value when the condition is false
+            return match then_ty {
+                Some(then_ty) if !then_ty.is(Id::UNIT) => {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            codes::MISMATCHED_TYPES,
+                            block_value_span(&if_expr.then_branch),
+                            "`if` may be missing an `else` clause",
+                        )
+                        .with_label(format!("expected `()`, found `{}`", self.display(&then_ty)))
+                        .with_note("an `if` without an `else` is `()`, so its block must be too")
+                        .with_help(format!(
+                            "add an `else` that's `{}` as well",
+                            self.display(&then_ty)
+                        )),
+                    );
+                    None
+                }
+                _ => Some(Type::of(Id::UNIT)),
+            };
+        };
+        let else_ty = match else_branch {
+            hir::Expr::If(else_if) => self.check_if(else_if, is_child),
+            else_branch => self.check_expr(else_branch),
+        };
+
+        match (then_ty, else_ty) {
+            (Some(then_ty), Some(else_ty)) if then_ty != else_ty => {
+                self.diagnostics.push(
+                    Diagnostic::error(
+                        codes::MISMATCHED_TYPES,
+                        branch_span(else_branch),
+                        "`if` and `else` have incompatible types",
+                    )
+                    .with_label(format!(
+                        "expected `{}`, found `{}`",
+                        self.display(&then_ty),
+                        self.display(&else_ty)
+                    ))
+                    .with_secondary(
+                        block_value_span(&if_expr.then_branch),
+                        format!("this is `{}`", self.display(&then_ty)),
+                    ),
+                );
+                None
+            }
+            (then_ty, else_ty) => then_ty.or(else_ty),
+        }
+    }
+
+    fn check_match(&mut self, match_expr: &hir::MatchExpr) -> Option<Type> {
+        let scrutinee_ty = self.check_expr(&match_expr.scrutinee);
+
+        let errors = self.diagnostics.len();
+        for arm in &match_expr.arms {
+            self.check_pattern(
+                &arm.pattern,
+                Some(&match_expr.scrutinee),
+                scrutinee_ty.as_ref(),
+            );
+        }
+        // Patterns that are wrong would only lead to more confusing errors about which values are
+        // matched
+        if self.diagnostics.len() == errors {
+            self.check_exhaustiveness(match_expr, scrutinee_ty.as_ref());
+        }
+
+        let mut expected: Option<(Type, Span)> = None;
+        let mut mismatched = false;
+        for arm in &match_expr.arms {
+            match (&expected, self.check_expr(&arm.body)) {
+                (None, Some(ty)) => expected = Some((ty, branch_span(&arm.body))),
+                (Some((expected_ty, expected_span)), Some(ty)) if ty != *expected_ty => {
+                    self.diagnostics.push(
+                        Diagnostic::error(
+                            codes::MISMATCHED_TYPES,
+                            branch_span(&arm.body),
+                            "`match` arms have incompatible types",
+                        )
+                        .with_label(format!(
+                            "expected `{}`, found `{}`",
+                            self.display(expected_ty),
+                            self.display(&ty)
+                        ))
+                        .with_secondary(
+                            *expected_span,
+                            format!("this is `{}`", self.display(expected_ty)),
+                        ),
+                    );
+                    mismatched = true;
+                }
+                _ => {}
+            }
+        }
+
+        if mismatched {
+            None
+        } else {
+            expected.map(|(ty, _)| ty)
+        }
+    }
+
+    /// Checks that a pattern matches values of the type it's matched against, `scrutinee` being
+    /// what's matched unless the pattern is for a field of it
+    fn check_pattern(
+        &mut self,
+        pattern: &hir::Pattern,
+        scrutinee: Option<&hir::Expr>,
+        ty: Option<&Type>,
+    ) {
+        let pattern_ty = match pattern {
+            hir::Pattern::Wildcard(_) | hir::Pattern::Error(_) => return,
+            hir::Pattern::Binding(binding) => {
+                if let Some(ty) = ty {
+                    self.locals.insert(binding.name, ty.clone());
+                }
+                return;
+            }
+            hir::Pattern::Constructor(constructor) if constructor.name == Id::ERROR => return,
+            hir::Pattern::Constructor(constructor) => {
+                let Kind::Constructor(of) = self.kind(constructor.name) else {
+                    let diagnostic = Diagnostic::error(
+                        codes::UNKNOWN_CONSTRUCTOR,
+                        constructor.span,
+                        format!("`{}` is not a constructor", self.name(constructor.name)),
+                    );
+                    self.diagnostics.push(match ty {
+                        Some(ty) => diagnostic
+                            .with_label(format!("not a constructor of `{}`", self.display(ty))),
+                        None => diagnostic.with_label("not a constructor"),
+                    });
+                    return;
+                };
+
+                if ty.is_none_or(|ty| ty.name == of) {
+                    self.check_fields(constructor, ty);
+                    return;
+                }
+                of
+            }
+            hir::Pattern::StringLiteral(_) => Id::STRING,
+            hir::Pattern::IntLiteral(_) => Id::INT,
+            hir::Pattern::BoolLiteral(_) => Id::BOOL,
+        };
+
+        match ty {
+            Some(ty) if !ty.is(pattern_ty) => {
+                let diagnostic =
+                    Diagnostic::error(codes::MISMATCHED_TYPES, pattern.span(), "mismatched types")
+                        .with_label(format!(
+                            "expected `{}`, found `{}`",
+                            self.display(ty),
+                            self.name(pattern_ty)
+                        ));
+                self.diagnostics.push(match scrutinee {
+                    Some(scrutinee) => diagnostic.with_secondary(
+                        scrutinee.span(),
+                        format!("this is `{}`", self.display(ty)),
+                    ),
+                    None => diagnostic,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// Checks that a constructor pattern has a pattern for each field of the constructor, and that
+    /// those match what the fields hold in a value of `ty`
+    fn check_fields(&mut self, constructor: &hir::ConstructorPattern, ty: Option<&Type>) {
+        let fields = hir::std_fields(constructor.name).unwrap_or_default();
+        if constructor.fields.len() != fields.len() {
+            let plural = |count| if count == 1 { "" } else { "s" };
+            self.diagnostics.push(
+                Diagnostic::error(
+                    codes::WRONG_NUMBER_OF_ARGUMENTS,
+                    constructor.span,
+                    format!(
+                        "`{}` has {} field{} but {} {} matched",
+                        self.name(constructor.name),
+                        fields.len(),
+                        plural(fields.len()),
+                        constructor.fields.len(),
+                        if constructor.fields.len() == 1 {
+                            "was"
+                        } else {
+                            "were"
+                        }
+                    ),
+                )
+                .with_label(format!(
+                    "expected {} field{}",
+                    fields.len(),
+                    plural(fields.len())
+                )),
+            );
+            return;
+        }
+
+        for (field, &param) in constructor.fields.iter().zip(fields) {
+            let field_ty = ty.and_then(|ty| ty.args.get(param)).cloned();
+            self.check_pattern(field, None, field_ty.as_ref());
+        }
+    }
+
+    /// Checks that the arms of a `match` cover every value it could be matching on and that each
+    /// of them matches something the ones before it didn't
+    fn check_exhaustiveness(&mut self, match_expr: &hir::MatchExpr, ty: Option<&Type>) {
+        let tys = [ty.cloned()];
+        let mut rows: Vec<Row> = Vec::new();
+        let mut spans = Vec::new();
+        // Once every value is matched the remaining arms can't be reached
+        let mut catch_all: Option<(Span, &str)> = None;
+
+        for arm in &match_expr.arms {
+            let span = arm.pattern.span();
+            let row = vec![Some(&arm.pattern)];
+
+            if !self.is_useful(&rows, &row, &tys) {
+                // Pointing at a single arm that matches everything this one does is the clearest,
+                // when it takes several of them the last one is where they all add up
+                let (reached_span, reason) = catch_all.unwrap_or_else(|| {
+                    rows.iter()
+                        .zip(&spans)
+                        .find(|(earlier, _)| {
+                            !self.is_useful(std::slice::from_ref(*earlier), &row, &tys)
+                        })
+                        .map_or_else(
+                            || {
+                                let last = *spans.last().expect("the first arm is always reached");
+                                (last, "everything this matches is matched by this point")
+                            },
+                            |(_, span)| (*span, "already matched here"),
+                        )
+                });
+                self.diagnostics.push(
+                    Diagnostic::error(codes::UNREACHABLE_PATTERN, span, "unreachable pattern")
+                        .with_label("this arm is never reached")
+                        .with_secondary(reached_span, reason),
+                );
+                continue;
+            }
+
+            rows.push(row);
+            spans.push(span);
+            if catch_all.is_none() && self.missing(&rows, &tys).is_empty() {
+                let reason = if matches_anything(Some(&arm.pattern)) {
+                    "this matches every value"
+                } else if ty.is_some_and(|ty| ty.is(Id::BOOL)) {
+                    "`true` and `false` are both matched by this point"
+                } else {
+                    "every value is matched by this point"
+                };
+                catch_all = Some((span, reason));
+            }
+        }
+
+        if catch_all.is_some() {
+            return;
+        }
+
+        let missing = self
+            .missing(&rows, &tys)
+            .into_iter()
+            .map(|witness| format!("`{}`", witness[0]))
+            .collect::<Vec<_>>();
+        let (list, plural) = match &missing[..] {
+            [only] => (only.clone(), ""),
+            [rest @ .., last] => (format!("{} and {last}", rest.join(", ")), "s"),
+            [] => unreachable!("there's a catch all when nothing is missing"),
+        };
+
+        let diagnostic = Diagnostic::error(
+            codes::NON_EXHAUSTIVE_MATCH,
+            match_expr.scrutinee.span(),
+            format!("non-exhaustive patterns: {list} not covered"),
+        )
+        .with_label(format!("pattern{plural} {list} not covered"))
+        .with_help("add an arm for each missing pattern, or a `_` arm to match the rest");
+
+        self.diagnostics.push(match ty {
+            Some(ty) if missing == ["`_`"] => diagnostic.with_note(format!(
+                "`{}` has too many values to match each of them with a literal",
+                self.display(ty)
+            )),
+            _ => diagnostic,
+        });
+    }
+
+    /// The constructors a value of a type is made with, which a `match` has to cover every one of,
+    /// `None` for types that have too many values to match each of them
+    ///
+    /// When the type isn't known the patterns matching it still tell which type it is
+    fn constructors(&self, ty: Option<&Type>, rows: &[Row]) -> Option<Vec<Constructor<'_>>> {
+        let name = match ty {
+            Some(ty) => ty.name,
+            None => rows
+                .iter()
+                .filter_map(|row| Constructor::of(row[0]?))
+                .find_map(|constructor| match constructor {
+                    Constructor::Bool(_) => Some(Id::BOOL),
+                    Constructor::Variant(id) => match self.kind(id) {
+                        Kind::Constructor(ty) => Some(ty),
+                        _ => None,
+                    },
+                    _ => None,
+                })?,
+        };
+
+        if name == Id::BOOL {
+            return Some(vec![Constructor::Bool(true), Constructor::Bool(false)]);
+        }
+        let variants = hir::std_constructors(name)
+            .map(Constructor::Variant)
+            .collect::<Vec<_>>();
+        (!variants.is_empty()).then_some(variants)
+    }
+
+    /// The types of what a constructor holds in a value of `ty`
+    fn field_tys(&self, constructor: Constructor, ty: Option<&Type>) -> Vec<Option<Type>> {
+        let Constructor::Variant(id) = constructor else {
+            return Vec::new();
+        };
+
+        hir::std_fields(id)
+            .unwrap_or_default()
+            .iter()
+            .map(|&param| ty.and_then(|ty| ty.args.get(param).cloned()))
+            .collect()
+    }
+
+    /// The values that none of the rows match, written as the patterns that would match them with
+    /// one pattern for each of `tys`
+    fn missing(&self, rows: &[Row], tys: &[Option<Type>]) -> Vec<Vec<String>> {
+        let Some((ty, rest)) = tys.split_first() else {
+            return match rows.is_empty() {
+                true => vec![Vec::new()],
+                false => Vec::new(),
+            };
+        };
+        if rows.is_empty() {
+            return vec![vec!["_".to_string(); tys.len()]];
+        }
+
+        let Some(constructors) = self.constructors(ty.as_ref(), rows) else {
+            let rows = rows
+                .iter()
+                .filter(|row| matches_anything(row[0]))
+                .map(|row| row[1..].to_vec())
+                .collect::<Vec<_>>();
+            return self
+                .missing(&rows, rest)
+                .into_iter()
+                .map(|witness| std::iter::once("_".to_string()).chain(witness).collect())
+                .collect();
+        };
+
+        constructors
+            .into_iter()
+            .flat_map(|constructor| {
+                let rows = specialize_all(rows, constructor);
+                let mut field_tys = self.field_tys(constructor, ty.as_ref());
+                let arity = field_tys.len();
+                field_tys.extend_from_slice(rest);
+
+                self.missing(&rows, &field_tys)
+                    .into_iter()
+                    .map(move |witness| {
+                        let (fields, rest) = witness.split_at(arity);
+                        std::iter::once(self.show(constructor, fields))
+                            .chain(rest.iter().cloned())
+                            .collect()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Whether a row matches any value that none of the rows before it do
+    fn is_useful(&self, rows: &[Row], row: &Row, tys: &[Option<Type>]) -> bool {
+        let Some((ty, rest)) = tys.split_first() else {
+            return rows.is_empty();
+        };
+
+        let useful_for = |constructor: Constructor| {
+            let mut field_tys = self.field_tys(constructor, ty.as_ref());
+            field_tys.extend_from_slice(rest);
+            let row = specialize(row, constructor).expect("the row to match its own constructor");
+
+            self.is_useful(&specialize_all(rows, constructor), &row, &field_tys)
+        };
+
+        if let Some(constructor) = row[0].and_then(Constructor::of) {
+            return useful_for(constructor);
+        }
+        match self.constructors(ty.as_ref(), rows) {
+            Some(constructors) => constructors.into_iter().any(useful_for),
+            None => {
+                let rows = rows
+                    .iter()
+                    .filter(|row| matches_anything(row[0]))
+                    .map(|row| row[1..].to_vec())
+                    .collect::<Vec<_>>();
+                self.is_useful(&rows, &row[1..].to_vec(), rest)
+            }
+        }
+    }
+
+    /// How a pattern matching what a constructor makes is written
+    fn show(&self, constructor: Constructor, fields: &[String]) -> String {
+        match constructor {
+            Constructor::String(string) => format!("{string:?}"),
+            Constructor::Int(int) => int.to_string(),
+            Constructor::Bool(bool) => bool.to_string(),
+            Constructor::Variant(id) if fields.is_empty() => self.name(id).to_string(),
+            Constructor::Variant(id) => format!("{}({})", self.name(id), fields.join(", ")),
+        }
+    }
+}
+
+/// The patterns an arm has left to match, `None` standing in for the fields of a value that the
+/// arm matches without looking at them
+type Row<'a> = Vec<Option<&'a hir::Pattern>>;
+
+/// What a pattern checks a value was made with before looking at what's inside of it, literals
+/// being constructors that hold nothing
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Constructor<'a> {
+    String(&'a str),
+    Int(i64),
+    Bool(bool),
+    Variant(Id),
+}
+
+impl<'a> Constructor<'a> {
+    /// Patterns that match every value don't check for a constructor
+    fn of(pattern: &'a hir::Pattern) -> Option<Self> {
+        match pattern {
+            hir::Pattern::StringLiteral(string_literal) => {
+                Some(Constructor::String(&string_literal.value))
+            }
+            hir::Pattern::IntLiteral(int_literal) => Some(Constructor::Int(int_literal.value)),
+            hir::Pattern::BoolLiteral(bool_literal) => Some(Constructor::Bool(bool_literal.value)),
+            hir::Pattern::Constructor(constructor) => Some(Constructor::Variant(constructor.name)),
+            hir::Pattern::Wildcard(_) | hir::Pattern::Binding(_) | hir::Pattern::Error(_) => None,
+        }
+    }
+
+    fn arity(self) -> usize {
+        match self {
+            Constructor::Variant(id) => hir::std_fields(id).map_or(0, <[_]>::len),
+            _ => 0,
+        }
+    }
+}
+
+fn matches_anything(pattern: Option<&hir::Pattern>) -> bool {
+    pattern.and_then(Constructor::of).is_none()
+}
+
+/// What's left of a row once its first value is known to be made with `constructor`, which is the
+/// patterns for its fields followed by the rest of the row, `None` when the row can't match it
+fn specialize<'a>(row: &Row<'a>, constructor: Constructor) -> Option<Row<'a>> {
+    let (first, rest) = row.split_first()?;
+    let fields = match first {
+        Some(hir::Pattern::Constructor(pattern))
+            if constructor == Constructor::Variant(pattern.name) =>
+        {
+            pattern.fields.iter().map(Some).collect()
+        }
+        Some(pattern) if Constructor::of(pattern).is_some() => {
+            if Constructor::of(pattern) != Some(constructor) {
+                return None;
+            }
+            Vec::new()
+        }
+        _ => vec![None; constructor.arity()],
+    };
+
+    Some(fields.into_iter().chain(rest.iter().copied()).collect())
+}
+
+fn specialize_all<'a>(rows: &[Row<'a>], constructor: Constructor) -> Vec<Row<'a>> {
+    rows.iter()
+        .filter_map(|row| specialize(row, constructor))
+        .collect()
+}
+
+/// Works out what the type parameters in `param` stand for by lining it up with the type of the
+/// arg passed for it, keeping what was worked out from earlier args
+fn infer(
+    param: &Type,
+    arg: &Type,
+    generics: &[hir::TypeParam],
+    substitutions: &mut HashMap<Id, Type>,
+) {
+    if is_generic(param.name, generics) {
+        substitutions
+            .entry(param.name)
+            .or_insert_with(|| arg.clone());
+        return;
+    }
+
+    if param.name == arg.name && param.args.len() == arg.args.len() {
+        for (param, arg) in param.args.iter().zip(&arg.args) {
+            infer(param, arg, generics, substitutions);
+        }
+    }
+}
+
+/// Replaces the type parameters in a type with what they stand for, `None` when one of them isn't
+/// known
+fn substitute(
+    ty: &Type,
+    generics: &[hir::TypeParam],
+    substitutions: &HashMap<Id, Type>,
+) -> Option<Type> {
+    if is_generic(ty.name, generics) {
+        return substitutions.get(&ty.name).cloned();
+    }
+
+    Some(Type {
+        name: ty.name,
+        args: ty
+            .args
+            .iter()
+            .map(|arg| substitute(arg, generics, substitutions))
+            .collect::<Option<_>>()?,
+    })
+}
+
+fn is_generic(id: Id, generics: &[hir::TypeParam]) -> bool {
+    generics.iter().any(|param| param.name == id)
+}
+
+/// Where the value of a block comes from, which is more helpful to point at than the whole block
+fn block_value_span(block: &hir::BlockExpr) -> Span {
+    block
+        .return_expression
+        .as_ref()
+        .map_or(block.span, hir::Expr::span)
+}
+
+fn branch_span(branch: &hir::Expr) -> Span {
+    match branch {
+        hir::Expr::Block(block) => block_value_span(block),
+        branch => branch.span(),
+    }
+}
+
+/// A count of type arguments written out with the right plural, as in `1 type argument`
+fn type_arguments(count: usize) -> String {
+    match count {
+        0 => "no type arguments".to_string(),
+        1 => "1 type argument".to_string(),
+        count => format!("{count} type arguments"),
+    }
+}
+
+/// Checks that every expression is used where its type is allowed to be
+pub fn check(hir: &Hir) -> Vec<Diagnostic> {
+    let mut checker = TypeChecker {
+        hir,
+        locals: HashMap::new(),
+        type_params: hir
+            .module
+            .values()
+            .flat_map(|item| match item {
+                hir::ModuleItem::Function(function) => &function.generics[..],
+                hir::ModuleItem::Effect(_) => &[],
+            })
+            .map(|param| param.name)
+            .collect(),
+        resumes: HashMap::new(),
+        diagnostics: Vec::new(),
+    };
+
+    for item in hir.module.values() {
+        let function = match item {
+            hir::ModuleItem::Function(function) => function,
+            hir::ModuleItem::Effect(effect) => {
+                for operation in &effect.operations {
+                    operation
+                        .inputs
+                        .iter()
+                        .for_each(|param| checker.check_ty(&param.ty));
+                    checker.check_ty(&operation.output);
+                }
+                continue;
+            }
+        };
+
+        for param in &function.inputs {
+            checker.check_ty(&param.ty);
+            if let Some(ty) = checker.known_ty(&param.ty) {
+                checker.locals.insert(param.name, ty);
+            }
+        }
+        checker.check_ty(&function.output.ty);
+        checker.check_effects(&function.output.effects);
+        if function.name == "main" {
+            checker.check_main_signature(function);
+            checker.check_main_effects(&function.output.effects);
+        }
+
+        checker.check_output(function);
+    }
+
+    checker.diagnostics
+}
@@ -1,7 +1,18 @@
-use std::collections::HashMap;
+mod span;
+mod string;
+mod trivia;
 
-use chumsky::{error::Rich, IterParser as _, ParseResult, Parser as _};
+use chumsky::{error::Rich, span::SimpleSpan, IterParser as _, ParseResult, Parser as _};
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
+use trivia::{doc_comments, item_trivia, trivia};
+
+pub use span::{LineCol, LineIndex, Span};
+
+/// Words that have a meaning of their own and so can't name anything
+const KEYWORDS: [&str; 11] = [
+    "fn", "let", "if", "else", "match", "handle", "with", "effect", "eff", "true", "false",
+];
 
 pub struct Parser<'a, 'b>
 where
@@ -12,183 +23,1033 @@ where
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct AST {
-    pub module: HashMap<String, ModuleItem>,
+    /// Top-level items keyed by their name, in the order they appear in the source
+    pub module: IndexMap<String, ModuleItem>,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Ident {
+    pub name: String,
+    pub span: Span,
+}
+
+/// A name made of one or more segments separated by `::`, such as `std::html::Html`
+///
+/// ref: https://doc.rust-lang.org/stable/reference/paths.html
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Path {
+    /// Never empty
+    pub segments: Vec<Ident>,
+    pub span: Span,
+}
+
+impl Path {
+    /// The last segment, which is the name of the item the path refers to
+    pub fn name(&self) -> &Ident {
+        self.segments
+            .last()
+            .expect("paths to have at least one segment")
+    }
+
+    /// The only segment of a path like `main`, which can refer to something local
+    pub fn as_ident(&self) -> Option<&Ident> {
+        match &self.segments[..] {
+            [ident] => Some(ident),
+            _ => None,
+        }
+    }
+}
+
+/// A type constructor applied to type arguments, such as `List<String>`
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct GenericTy {
+    pub constructor: Path,
+    /// Never empty, `List<>` isn't valid syntax
+    pub args: Vec<Ty>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum Ty {
-    // TODO: This can be upgraded later to Path
-    // ref: https://doc.rust-lang.org/stable/reference/paths.html#paths-in-types
-    Simple(String),
+    Path(Path),
+    Generic(GenericTy),
+    /// `()`, the type of things that don't have a meaningful value
+    Unit(Span),
+}
+
+impl Ty {
+    pub fn span(&self) -> Span {
+        match self {
+            Ty::Path(path) => path.span,
+            Ty::Generic(generic) => generic.span,
+            Ty::Unit(span) => *span,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum Eff {
-    // TODO: This can be upgraded later to Path as well
-    // ref: https://doc.rust-lang.org/stable/reference/paths.html#paths-in-types
-    Simple(String),
+    Path(Path),
+}
+
+impl Eff {
+    pub fn span(&self) -> Span {
+        match self {
+            Eff::Path(path) => path.span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct StringLiteralExpr {
+    /// The value of the string after its escape sequences were decoded
+    pub value: String,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct IntLiteralExpr {
+    pub value: i64,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct FloatLiteralExpr {
+    pub value: f64,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct BoolLiteralExpr {
+    pub value: bool,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct FunctionCallExpr {
-    pub name: String,
+    pub name: Path,
     pub args: Vec<Expr>,
     pub children: Vec<Expr>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct BlockExpr {
     pub statements: Vec<Statement>,
     pub return_expression: Option<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct IfExpr {
+    pub condition: Expr,
+    pub then_branch: BlockExpr,
+    /// Either a block or another `if` when it's an `else if`
+    pub else_branch: Option<Expr>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct ConstructorPattern {
+    pub name: Ident,
+    pub fields: Vec<Pattern>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub enum Pattern {
+    /// `_`, which matches anything without binding it
+    Wildcard(Span),
+    /// A name which matches anything and binds it to that name
+    Binding(Ident),
+    StringLiteral(StringLiteralExpr),
+    IntLiteral(IntLiteralExpr),
+    BoolLiteral(BoolLiteralExpr),
+    Constructor(ConstructorPattern),
+    /// Placeholder for a pattern that isn't allowed, the error for it was already reported
+    Error(Span),
+}
+
+impl Pattern {
+    pub fn span(&self) -> Span {
+        match self {
+            Pattern::Wildcard(span) | Pattern::Error(span) => *span,
+            Pattern::Binding(name) => name.span,
+            Pattern::StringLiteral(string_literal) => string_literal.span,
+            Pattern::IntLiteral(int_literal) => int_literal.span,
+            Pattern::BoolLiteral(bool_literal) => bool_literal.span,
+            Pattern::Constructor(constructor) => constructor.span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct MatchArm {
+    pub pattern: Pattern,
+    pub body: Expr,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct MatchExpr {
+    /// The value being matched on
+    pub scrutinee: Expr,
+    pub arms: Vec<MatchArm>,
+    pub span: Span,
+}
+
+/// One of the clauses of a handler, `log(msg) => resume(())`
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct HandlerClause {
+    /// Which operation of the handled effect the clause is for
+    pub operation: Ident,
+    pub params: Vec<Ident>,
+    pub body: Expr,
+    pub span: Span,
+}
+
+/// `handle { ... } with Console { log(msg) => resume(()) }`
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct HandleExpr {
+    pub body: BlockExpr,
+    pub effect: Path,
+    pub clauses: Vec<HandlerClause>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum Expr {
-    StringLiteral(String),
+    StringLiteral(StringLiteralExpr),
+    IntLiteral(IntLiteralExpr),
+    FloatLiteral(FloatLiteralExpr),
+    BoolLiteral(BoolLiteralExpr),
+    /// A name on its own, which is either a variable or a function called without any arguments
+    Variable(Ident),
     FunctionCall(FunctionCallExpr),
-    // TODO: Handle BlockExpr
+    Block(Box<BlockExpr>),
+    If(Box<IfExpr>),
+    Match(Box<MatchExpr>),
+    Handle(Box<HandleExpr>),
+    /// `()`
+    Unit(Span),
+    /// Placeholder for an expression that failed to parse, the error for it was already reported
+    Error(Span),
+}
+
+impl Expr {
+    pub fn span(&self) -> Span {
+        match self {
+            Expr::StringLiteral(string_literal) => string_literal.span,
+            Expr::IntLiteral(int_literal) => int_literal.span,
+            Expr::FloatLiteral(float_literal) => float_literal.span,
+            Expr::BoolLiteral(bool_literal) => bool_literal.span,
+            Expr::Variable(name) => name.span,
+            Expr::FunctionCall(call) => call.span,
+            Expr::Block(block) => block.span,
+            Expr::If(if_expr) => if_expr.span,
+            Expr::Match(match_expr) => match_expr.span,
+            Expr::Handle(handle) => handle.span,
+            Expr::Unit(span) => *span,
+            Expr::Error(span) => *span,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct LetStatement {
+    pub name: Ident,
+    pub ty: Option<Ty>,
+    pub value: Expr,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum Statement {
+    Let(LetStatement),
     ExprStatement(Expr),
 }
 
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Let(let_statement) => let_statement.span,
+            Statement::ExprStatement(expr) => expr.span(),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct FunctionOutput {
     pub ty: Ty,
-    pub eff: Option<Eff>,
+    /// The effects after `eff`, separated by `+`, empty when the function is pure
+    pub effects: Vec<Eff>,
+    pub span: Span,
+}
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Param {
+    pub name: Ident,
+    pub ty: Ty,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub struct Function {
-    pub name: String,
-    pub inputs: (),
+    /// The `///` comments right before the function, one line of the string per line of comment
+    pub doc: Option<String>,
+    pub name: Ident,
+    /// The type parameters between `<` and `>` after the name, if there are any
+    pub generics: Vec<Ident>,
+    pub inputs: Vec<Param>,
     pub output: FunctionOutput,
     pub body: BlockExpr,
+    pub span: Span,
+}
+
+/// Something an effect lets functions do, declared like a function but without a body since what
+/// it does is up to whoever performs the effect
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Operation {
+    pub doc: Option<String>,
+    pub name: Ident,
+    pub inputs: Vec<Param>,
+    pub output: Ty,
+    pub span: Span,
+}
+
+/// `effect Console { fn log(msg: String) -> (); }`
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+pub struct Effect {
+    pub doc: Option<String>,
+    pub name: Ident,
+    pub operations: Vec<Operation>,
+    pub span: Span,
 }
 
 #[derive(Debug, PartialEq, Deserialize, Serialize)]
 pub enum ModuleItem {
-    Function(Function),
+    Function(Box<Function>),
+    Effect(Effect),
+}
+
+impl ModuleItem {
+    pub fn name(&self) -> &str {
+        match self {
+            ModuleItem::Function(function) => &function.name.name,
+            ModuleItem::Effect(effect) => &effect.name.name,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            ModuleItem::Function(function) => function.span,
+            ModuleItem::Effect(effect) => effect.span,
+        }
+    }
+}
+
+/// Whether a lone name refers to a variable or is a call to a function without any arguments can
+/// only be known once names are resolved
+fn call_or_variable(
+    mut name: Path,
+    args: Option<Vec<Expr>>,
+    children: Option<Vec<Expr>>,
+    span: Span,
+) -> Expr {
+    match (args, children) {
+        (None, None) if name.segments.len() == 1 => {
+            Expr::Variable(name.segments.pop().expect("there to be one segment"))
+        }
+        (args, children) => Expr::FunctionCall(FunctionCallExpr {
+            name,
+            args: args.unwrap_or_default(),
+            children: children.unwrap_or_default(),
+            span,
+        }),
+    }
 }
 
 impl<'a, 'b> Parser<'a, 'b> {
     pub fn new() -> Self {
         use chumsky::{
-            primitive::{choice, end, just, none_of},
-            recursive::{Indirect, Recursive},
-            text::ident,
+            primitive::{any, choice, end, just, none_of, one_of},
+            recovery::{nested_delimiters, via_parser},
+            recursive::{recursive, Indirect, Recursive},
+            text::{ident, keyword},
         };
 
+        // Parsers for nodes never consume the whitespace around them, that's left to whoever is
+        // using them so that the spans of nodes are tight around their contents
+
         let mut expr_parser =
             Recursive::<Indirect<&str, Expr, chumsky::extra::Err<Rich<'a, char>>>>::declare();
         let mut statement_parser =
             Recursive::<Indirect<&str, Statement, chumsky::extra::Err<Rich<'a, char>>>>::declare();
 
-        let string_literal_expr = none_of("\\\"")
-            .ignored()
-            .repeated()
-            .slice()
-            .map(ToString::to_string)
-            .map(Expr::StringLiteral)
-            .delimited_by(just('"'), just('"'));
+        let ident_parser = ident()
+            .try_map(|name: &str, span: SimpleSpan| {
+                if KEYWORDS.contains(&name) {
+                    Err(Rich::custom(
+                        span,
+                        format!("`{name}` is a keyword and can't be used as an identifier"),
+                    ))
+                } else {
+                    Ok(name)
+                }
+            })
+            .map_with_span(|name: &str, span: SimpleSpan| Ident {
+                name: name.to_string(),
+                span: span.into(),
+            })
+            .labelled("identifier");
 
-        let function_call_parser = ident()
-            .padded()
+        let path_parser = ident_parser
+            .separated_by(just("::").padded_by(trivia()))
+            .at_least(1)
+            .collect::<Vec<Ident>>()
+            .map_with_span(|segments, span: SimpleSpan| Path {
+                segments,
+                span: span.into(),
+            });
+
+        let string_literal_expr =
+            string::string_literal().map_with_span(|value, span: SimpleSpan| {
+                Expr::StringLiteral(StringLiteralExpr {
+                    value,
+                    span: span.into(),
+                })
+            });
+
+        // Digits can be separated by underscores, as in `1_000_000`, but can't start with one
+        let digits = one_of('0'..='9')
+            .then(one_of('0'..='9').or(just('_')).repeated())
+            .slice();
+
+        let number_literal_expr = just('-')
+            .or_not()
+            .then(digits.clone())
             .then(
+                just('.').then(digits.clone()).or_not().then(
+                    one_of("eE")
+                        .then(one_of("+-").or_not())
+                        .then(digits)
+                        .or_not(),
+                ),
+            )
+            .slice()
+            .validate(|number: &str, span: SimpleSpan, emitter| {
+                let is_float = number.contains(['.', 'e', 'E']);
+                let number = number.replace('_', "");
+
+                if is_float {
+                    // Parsing a float that matched the grammar above can't fail, it can at most
+                    // round to infinity when it's too large
+                    let value = number.parse().unwrap_or(f64::NAN);
+                    Expr::FloatLiteral(FloatLiteralExpr {
+                        value,
+                        span: span.into(),
+                    })
+                } else {
+                    let value = number.parse().unwrap_or_else(|_| {
+                        emitter.emit(Rich::custom(
+                            span,
+                            "integer literal is too large to fit in an `Int`",
+                        ));
+                        0
+                    });
+                    Expr::IntLiteral(IntLiteralExpr {
+                        value,
+                        span: span.into(),
+                    })
+                }
+            });
+
+        let bool_literal_expr = keyword("true")
+            .to(true)
+            .or(keyword("false").to(false))
+            .map_with_span(|value, span: SimpleSpan| {
+                Expr::BoolLiteral(BoolLiteralExpr {
+                    value,
+                    span: span.into(),
+                })
+            });
+
+        let call_args = trivia()
+            .ignore_then(
                 expr_parser
                     .clone()
-                    .delimited_by(just("("), just(")"))
+                    .padded_by(trivia())
                     .separated_by(just(","))
+                    .allow_trailing()
                     .collect::<Vec<Expr>>()
-                    .padded(),
+                    .padded_by(trivia())
+                    .delimited_by(just("("), just(")"))
+                    .recover_with(via_parser(nested_delimiters(
+                        '(',
+                        ')',
+                        [('{', '}')],
+                        |span: SimpleSpan| vec![Expr::Error(span.into())],
+                    ))),
             )
-            .then(
+            .or_not();
+
+        let call_children = trivia()
+            .ignore_then(
                 expr_parser
                     .clone()
+                    .padded_by(trivia())
                     .separated_by(just(","))
+                    .allow_trailing()
                     .collect::<Vec<Expr>>()
-                    .delimited_by(just("{").padded(), just("}").padded())
-                    .or_not(),
+                    .padded_by(trivia())
+                    .delimited_by(just("{"), just("}"))
+                    .recover_with(via_parser(nested_delimiters(
+                        '{',
+                        '}',
+                        [('(', ')')],
+                        |span: SimpleSpan| vec![Expr::Error(span.into())],
+                    ))),
             )
-            .map(|((name, args), children)| {
-                Expr::FunctionCall(FunctionCallExpr {
-                    name: name.to_string(),
-                    args,
-                    children: children.unwrap_or_default(),
-                })
+            .or_not();
+
+        let function_call_parser = path_parser
+            .clone()
+            .then(call_args.clone())
+            .then(call_children)
+            .map_with_span(|((name, args), children), span: SimpleSpan| {
+                call_or_variable(name, args, children, span.into())
             });
 
-        let block_parser = just("{")
-            .ignore_then(
-                statement_parser
+        // The block after the condition of an `if` or the value of a `match` would otherwise be
+        // taken as the children of a call, so calls in those places can't have any
+        let childless_call_parser =
+            path_parser
+                .clone()
+                .then(call_args)
+                .map_with_span(|(name, args), span: SimpleSpan| {
+                    call_or_variable(name, args, None, span.into())
+                });
+
+        let block_parser = statement_parser
+            .clone()
+            .padded_by(trivia())
+            .repeated()
+            .collect::<Vec<Statement>>()
+            .then(expr_parser.clone().padded_by(trivia()).or_not())
+            .padded_by(trivia())
+            .delimited_by(just("{"), just("}"))
+            .map_with_span(
+                |(statements, return_expression), span: SimpleSpan| BlockExpr {
+                    statements,
+                    return_expression,
+                    span: span.into(),
+                },
+            )
+            .recover_with(via_parser(nested_delimiters(
+                '{',
+                '}',
+                [('(', ')')],
+                |span: SimpleSpan| BlockExpr {
+                    statements: Vec::new(),
+                    return_expression: Some(Expr::Error(span.into())),
+                    span: span.into(),
+                },
+            )));
+
+        let condition_parser = choice((
+            string_literal_expr.clone(),
+            number_literal_expr.clone(),
+            bool_literal_expr.clone(),
+            block_parser
+                .clone()
+                .map(|block| Expr::Block(Box::new(block))),
+            childless_call_parser,
+        ))
+        .labelled("expression");
+
+        let if_parser = recursive(|if_parser| {
+            keyword("if")
+                .labelled("`if`")
+                .ignore_then(condition_parser.clone().padded_by(trivia()))
+                .then(block_parser.clone())
+                .then(
+                    trivia()
+                        .ignore_then(keyword("else").labelled("`else`"))
+                        .ignore_then(trivia())
+                        .ignore_then(choice((
+                            block_parser
+                                .clone()
+                                .map(|block| Expr::Block(Box::new(block))),
+                            if_parser,
+                        )))
+                        .or_not(),
+                )
+                .map_with_span(
+                    |((condition, then_branch), else_branch), span: SimpleSpan| {
+                        Expr::If(Box::new(IfExpr {
+                            condition,
+                            then_branch,
+                            else_branch,
+                            span: span.into(),
+                        }))
+                    },
+                )
+        });
+
+        let literal_pattern = choice((
+            string_literal_expr.clone(),
+            number_literal_expr.clone(),
+            bool_literal_expr.clone(),
+        ))
+        .validate(|literal, span: SimpleSpan, emitter| match literal {
+            Expr::StringLiteral(literal) => Pattern::StringLiteral(literal),
+            Expr::IntLiteral(literal) => Pattern::IntLiteral(literal),
+            Expr::BoolLiteral(literal) => Pattern::BoolLiteral(literal),
+            // Floats can't be reliably compared for equality
+            _ => {
+                emitter.emit(Rich::custom(
+                    span,
+                    "float literals can't be used in patterns",
+                ));
+                Pattern::Error(span.into())
+            }
+        });
+
+        let pattern_parser = recursive(|pattern_parser| {
+            let constructor_pattern = ident_parser
+                .then(
+                    pattern_parser
+                        .padded_by(trivia())
+                        .separated_by(just(","))
+                        .allow_trailing()
+                        .collect::<Vec<Pattern>>()
+                        .padded_by(trivia())
+                        .delimited_by(just("("), just(")")),
+                )
+                .map_with_span(|(name, fields), span: SimpleSpan| {
+                    Pattern::Constructor(ConstructorPattern {
+                        name,
+                        fields,
+                        span: span.into(),
+                    })
+                });
+
+            choice((
+                literal_pattern,
+                constructor_pattern,
+                ident_parser.map(|name| match &name.name[..] {
+                    "_" => Pattern::Wildcard(name.span),
+                    _ => Pattern::Binding(name),
+                }),
+            ))
+            .labelled("pattern")
+        });
+
+        let match_arm_parser = pattern_parser
+            .then_ignore(just("=>").padded_by(trivia()))
+            .then(expr_parser.clone())
+            .map_with_span(|(pattern, body), span: SimpleSpan| MatchArm {
+                pattern,
+                body,
+                span: span.into(),
+            });
+
+        let match_parser = keyword("match")
+            .labelled("`match`")
+            .ignore_then(condition_parser.padded_by(trivia()))
+            .then(
+                match_arm_parser
+                    .padded_by(trivia())
+                    .separated_by(just(","))
+                    .allow_trailing()
+                    .collect::<Vec<MatchArm>>()
+                    .padded_by(trivia())
+                    .delimited_by(just("{"), just("}"))
+                    .recover_with(via_parser(nested_delimiters(
+                        '{',
+                        '}',
+                        [('(', ')')],
+                        |_| Vec::new(),
+                    ))),
+            )
+            .map_with_span(|(scrutinee, arms), span: SimpleSpan| {
+                Expr::Match(Box::new(MatchExpr {
+                    scrutinee,
+                    arms,
+                    span: span.into(),
+                }))
+            });
+
+        let handler_clause_parser = ident_parser
+            .then(
+                ident_parser
+                    .padded_by(trivia())
+                    .separated_by(just(","))
+                    .allow_trailing()
+                    .collect::<Vec<Ident>>()
+                    .padded_by(trivia())
+                    .delimited_by(just("("), just(")")),
+            )
+            .then_ignore(just("=>").padded_by(trivia()))
+            .then(expr_parser.clone())
+            .map_with_span(
+                |((operation, params), body), span: SimpleSpan| HandlerClause {
+                    operation,
+                    params,
+                    body,
+                    span: span.into(),
+                },
+            );
+
+        let handle_parser = keyword("handle")
+            .labelled("`handle`")
+            .ignore_then(block_parser.clone().padded_by(trivia()))
+            .then_ignore(keyword("with").labelled("`with`"))
+            .then_ignore(trivia())
+            .then(path_parser.clone())
+            .then(
+                handler_clause_parser
+                    .padded_by(trivia())
+                    .separated_by(just(","))
+                    .allow_trailing()
+                    .collect::<Vec<HandlerClause>>()
+                    .padded_by(trivia())
+                    .delimited_by(just("{"), just("}"))
+                    .padded_by(trivia()),
+            )
+            .validate(|((body, effect), clauses), span: SimpleSpan, emitter| {
+                for (i, clause) in clauses.iter().enumerate() {
+                    let name = &clause.operation.name;
+                    if clauses[..i]
+                        .iter()
+                        .any(|earlier| &earlier.operation.name == name)
+                    {
+                        emitter.emit(Rich::custom(
+                            clause.span.into(),
+                            format!("the operation `{name}` is handled multiple times"),
+                        ));
+                    }
+                }
+
+                Expr::Handle(Box::new(HandleExpr {
+                    body,
+                    effect,
+                    clauses,
+                    span: span.into(),
+                }))
+            });
+
+        let unit_expr = just("(")
+            .then(trivia())
+            .then(just(")"))
+            .map_with_span(|_, span: SimpleSpan| Expr::Unit(span.into()));
+
+        expr_parser.define(
+            choice((
+                string_literal_expr,
+                number_literal_expr,
+                bool_literal_expr,
+                unit_expr,
+                block_parser
                     .clone()
-                    .padded()
-                    .repeated()
-                    .collect::<Vec<Statement>>(),
+                    .map(|block| Expr::Block(Box::new(block))),
+                if_parser.clone(),
+                match_parser.clone(),
+                handle_parser.clone(),
+                function_call_parser,
+            ))
+            .labelled("expression"),
+        );
+
+        let unit_ty = just("(")
+            .then(trivia())
+            .then(just(")"))
+            .map_with_span(|_, span: SimpleSpan| Ty::Unit(span.into()));
+
+        let ty_parser = recursive(|ty_parser| {
+            let path_or_generic = path_parser
+                .clone()
+                .then(
+                    trivia()
+                        .ignore_then(
+                            ty_parser
+                                .padded_by(trivia())
+                                .separated_by(just(","))
+                                .allow_trailing()
+                                .at_least(1)
+                                .collect::<Vec<Ty>>()
+                                .padded_by(trivia())
+                                .delimited_by(just("<"), just(">")),
+                        )
+                        .or_not(),
+                )
+                .map_with_span(|(constructor, args), span: SimpleSpan| match args {
+                    None => Ty::Path(constructor),
+                    Some(args) => Ty::Generic(GenericTy {
+                        constructor,
+                        args,
+                        span: span.into(),
+                    }),
+                });
+
+            path_or_generic.or(unit_ty)
+        });
+
+        let let_parser = keyword("let")
+            .labelled("`let`")
+            .ignore_then(trivia())
+            .ignore_then(ident_parser)
+            .then(
+                just(":")
+                    .padded_by(trivia())
+                    .ignore_then(ty_parser.clone())
+                    .or_not(),
             )
-            .then(expr_parser.clone().padded().or_not())
-            .then_ignore(just("}"))
-            .map(|(statements, return_expression)| BlockExpr {
-                statements,
-                return_expression,
+            .then_ignore(just("=").padded_by(trivia()))
+            .then(expr_parser.clone())
+            .map_with_span(|((name, ty), value), span: SimpleSpan| {
+                Statement::Let(LetStatement {
+                    name,
+                    ty,
+                    value,
+                    span: span.into(),
+                })
             });
 
-        expr_parser.define(choice((function_call_parser, string_literal_expr)));
+        // Block-like expressions don't need a `;` to be used as statements, unless they're the last
+        // thing in a block in which case they're what it returns
+        let block_like_statement = block_parser
+            .clone()
+            .map(|block| Expr::Block(Box::new(block)))
+            .or(if_parser)
+            .or(match_parser)
+            .or(handle_parser)
+            .map(Statement::ExprStatement)
+            .then_ignore(choice((
+                just(";").padded_by(trivia()).ignored(),
+                trivia().then(just("}")).not(),
+            )));
+
         statement_parser.define(
-            expr_parser
-                .then_ignore(just(";"))
-                .map(Statement::ExprStatement),
+            choice((
+                let_parser.then_ignore(just(";").padded_by(trivia())),
+                block_like_statement,
+                expr_parser
+                    .clone()
+                    .map(Statement::ExprStatement)
+                    .then_ignore(just(";").padded_by(trivia())),
+            ))
+            // A broken statement is skipped up to its `;`, as long as that doesn't mean leaving
+            // the block it's in
+            .recover_with(via_parser(
+                none_of(";{}")
+                    .repeated()
+                    .at_least(1)
+                    .map_with_span(|_, span: SimpleSpan| {
+                        Statement::ExprStatement(Expr::Error(span.into()))
+                    })
+                    .then_ignore(just(";")),
+            )),
         );
 
-        let function_output_parser = ident()
-            .padded()
-            .then(just("eff").ignore_then(ident().padded()).or_not())
-            .map(|(ty, eff): (&str, Option<&str>)| FunctionOutput {
-                ty: Ty::Simple(ty.to_string()),
-                eff: eff.map(|eff| Eff::Simple(eff.to_string())),
+        let param_parser = ident_parser
+            .then_ignore(just(":").padded_by(trivia()))
+            .then(ty_parser.clone())
+            .map_with_span(|(name, ty), span: SimpleSpan| Param {
+                name,
+                ty,
+                span: span.into(),
             });
 
-        let fn_parser = just("fn")
-            .then_ignore(just(" "))
-            .padded()
-            .ignore_then(ident())
-            .then_ignore(just("()"))
-            .then_ignore(just("->").padded())
-            .then(function_output_parser)
+        let params_parser = param_parser
+            .padded_by(trivia())
+            .separated_by(just(","))
+            .allow_trailing()
+            .collect::<Vec<Param>>()
+            .padded_by(trivia())
+            .delimited_by(just("("), just(")"))
+            .padded_by(trivia());
+
+        let function_output_parser = ty_parser
+            .clone()
+            .then(
+                trivia()
+                    .ignore_then(keyword("eff").labelled("`eff`"))
+                    .ignore_then(trivia())
+                    .ignore_then(
+                        path_parser
+                            .map(Eff::Path)
+                            .separated_by(just("+").padded_by(trivia()))
+                            .at_least(1)
+                            .collect::<Vec<Eff>>(),
+                    )
+                    .or_not()
+                    .map(Option::unwrap_or_default),
+            )
+            .map_with_span(|(ty, effects), span: SimpleSpan| FunctionOutput {
+                ty,
+                effects,
+                span: span.into(),
+            });
+
+        let fn_parser = keyword("fn")
+            .labelled("`fn`")
+            .then_ignore(trivia())
+            .ignore_then(ident_parser)
+            .then(
+                ident_parser
+                    .padded_by(trivia())
+                    .separated_by(just(","))
+                    .allow_trailing()
+                    .collect::<Vec<Ident>>()
+                    .padded_by(trivia())
+                    .delimited_by(just("<"), just(">"))
+                    .padded_by(trivia())
+                    .or_not()
+                    .map(Option::unwrap_or_default),
+            )
+            .then(params_parser.clone())
+            .then_ignore(just("->").labelled("`->`"))
+            .then(function_output_parser.padded_by(trivia()))
             .then(block_parser)
-            .map(|((name, output), body)| {
-                ModuleItem::Function(Function {
-                    name: name.to_string(),
-                    inputs: (),
+            .map_with_span(
+                |((((name, generics), inputs), output), body), span: SimpleSpan| Function {
+                    doc: None,
+                    name,
+                    generics,
+                    inputs,
                     output,
                     body,
+                    span: span.into(),
+                },
+            );
+
+        let operation_parser = doc_comments()
+            .then_ignore(keyword("fn").labelled("`fn`"))
+            .then_ignore(trivia())
+            .then(ident_parser)
+            .then(params_parser)
+            .then_ignore(just("->").labelled("`->`"))
+            .then(ty_parser.padded_by(trivia()))
+            .then_ignore(just(";"))
+            .map_with_span(
+                |(((doc, name), inputs), output), span: SimpleSpan| Operation {
+                    doc,
+                    name,
+                    inputs,
+                    output,
+                    span: span.into(),
+                },
+            );
+
+        let effect_parser = keyword("effect")
+            .labelled("`effect`")
+            .then_ignore(trivia())
+            .ignore_then(ident_parser)
+            .then(
+                operation_parser
+                    .padded_by(item_trivia())
+                    .repeated()
+                    .collect::<Vec<Operation>>()
+                    .delimited_by(just("{"), just("}"))
+                    .padded_by(trivia()),
+            )
+            .validate(|(name, operations), span: SimpleSpan, emitter| {
+                for (i, operation) in operations.iter().enumerate() {
+                    let name = &operation.name.name;
+                    if operations[..i]
+                        .iter()
+                        .any(|earlier| &earlier.name.name == name)
+                    {
+                        emitter.emit(Rich::custom(
+                            operation.span.into(),
+                            format!("the operation `{name}` is declared multiple times"),
+                        ));
+                    }
+                }
+
+                Effect {
+                    doc: None,
+                    name,
+                    operations,
+                    span: span.into(),
+                }
+            });
+
+        let item_keyword = keyword("fn").or(keyword("effect"));
+
+        // Skips whole identifiers at a time so that an identifier merely containing `fn` isn't
+        // mistaken for the start of the next item
+        let skip_to_next_item = ident()
+            .and_is(item_keyword.clone().not())
+            .ignored()
+            .or(any().and_is(item_keyword.clone().not()).ignored());
+
+        let dangling_doc_comments = doc_comments()
+            .filter(Option::is_some)
+            .then_ignore(end())
+            .validate(|_, span: SimpleSpan, emitter| {
+                emitter.emit(Rich::custom(
+                    span,
+                    "doc comments must be followed by the item they document",
+                ));
+                None
+            });
+
+        let module_parser = doc_comments()
+            .then(choice((
+                fn_parser.map(|function| ModuleItem::Function(Box::new(function))),
+                effect_parser.map(ModuleItem::Effect),
+            )))
+            .map(|(doc, item)| {
+                Some(match item {
+                    ModuleItem::Function(function) => {
+                        ModuleItem::Function(Box::new(Function { doc, ..*function }))
+                    }
+                    ModuleItem::Effect(effect) => ModuleItem::Effect(Effect { doc, ..effect }),
                 })
             })
-            .padded()
+            .or(dangling_doc_comments)
+            // An item that can't be made sense of is dropped and parsing resumes at the next `fn`
+            // or `effect`
+            .recover_with(via_parser(
+                item_keyword
+                    .ignored()
+                    .or(skip_to_next_item.clone())
+                    .then(skip_to_next_item.repeated())
+                    .map(|_| None),
+            ))
+            .padded_by(item_trivia())
+            .repeated()
+            .collect::<Vec<_>>()
+            .padded_by(item_trivia())
             .then_ignore(end())
-            .map(|module_item| AST {
-                module: [("main".to_string(), module_item)].into_iter().collect(),
+            .validate(|module_items, _, emitter| {
+                let mut module = IndexMap::with_capacity(module_items.len());
+
+                for module_item in module_items.into_iter().flatten() {
+                    let name = module_item.name();
+                    if module.contains_key(name) {
+                        emitter.emit(Rich::custom(
+                            module_item.span().into(),
+                            format!("the name `{name}` is defined multiple times"),
+                        ));
+                    } else {
+                        module.insert(name.to_string(), module_item);
+                    }
+                }
+
+                AST { module }
             })
             .boxed();
 
-        Parser { inner: fn_parser }
+        Parser {
+            inner: module_parser,
+        }
     }
 
-    pub fn parse(&self, file: &'a str) -> ParseResult<AST, Rich<char>> {
-        let ret = self.inner.parse(file);
-
-        // TODO: For initial debugging purposes only
-        ret.errors().for_each(|error| {
-            let span = error.span();
-            let start = span.start.saturating_sub(5);
-            let end = span.end.max(file.len());
-
-            eprintln!("Error at: {}", &file[start..end]);
-        });
-
-        ret
+    pub fn parse(&self, file: &'a str) -> ParseResult<AST, Rich<'_, char>> {
+        self.inner.parse(file)
     }
 }
 
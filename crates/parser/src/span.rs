@@ -0,0 +1,117 @@
+use chumsky::span::SimpleSpan;
+use serde::{Deserialize, Serialize};
+
+/// A byte range into the source file, this is what chumsky's [SimpleSpan] gets converted into so it
+/// can be serialized and carried around by every later stage of the compiler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The smallest span that covers both spans
+    pub fn union(self, other: Span) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+impl From<SimpleSpan<usize>> for Span {
+    fn from(span: SimpleSpan<usize>) -> Self {
+        Self {
+            start: span.start,
+            end: span.end,
+        }
+    }
+}
+
+impl From<Span> for SimpleSpan<usize> {
+    fn from(span: Span) -> Self {
+        SimpleSpan::new(span.start, span.end)
+    }
+}
+
+/// A zero-indexed line and column pair, the column is counted in chars not bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct LineCol {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Maps byte offsets of a source file to lines and columns
+pub struct LineIndex<'a> {
+    source: &'a str,
+    /// The byte offset each line starts at, the first line always starts at 0
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+
+        Self {
+            source,
+            line_starts,
+        }
+    }
+
+    pub fn source(&self) -> &'a str {
+        self.source
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Offsets past the end of the file are clamped to the end of the file
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        let offset = offset.min(self.source.len());
+        let line = self
+            .line_starts
+            .partition_point(|&line_start| line_start <= offset)
+            - 1;
+        let column = self.source[self.line_starts[line]..offset].chars().count();
+
+        LineCol { line, column }
+    }
+
+    /// The text of a line without its line ending
+    pub fn line(&self, line: usize) -> &'a str {
+        let start = self.line_starts[line];
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .copied()
+            .unwrap_or(self.source.len());
+
+        self.source[start..end].trim_end_matches(['\n', '\r'])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LineCol, LineIndex};
+
+    #[test]
+    fn line_col() {
+        let index = LineIndex::new("fn main() -> Html {\n    \"héllo\"\r\n}\n");
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), LineCol { line: 0, column: 0 });
+        assert_eq!(index.line_col(3), LineCol { line: 0, column: 3 });
+        assert_eq!(index.line_col(20), LineCol { line: 1, column: 0 });
+        // The é is two bytes long but only one column wide
+        assert_eq!(index.line_col(28), LineCol { line: 1, column: 7 });
+        assert_eq!(index.line(1), "    \"héllo\"");
+        assert_eq!(index.line_col(1000), LineCol { line: 3, column: 0 });
+    }
+}
@@ -0,0 +1,167 @@
+use chumsky::{
+    error::Rich,
+    extra::Err,
+    primitive::{any, choice, just, none_of},
+    span::SimpleSpan,
+    text, ConfigIterParser as _, IterParser as _, Parser,
+};
+
+/// A decoded char of a string and whether it was written literally in the source rather than being
+/// produced by an escape sequence, text blocks need to know that to find their indentation
+type StringChar = (char, bool);
+
+fn escape<'a>() -> impl Parser<'a, &'a str, char, Err<Rich<'a, char>>> + Clone {
+    let simple_escape = just('\\').ignore_then(choice((
+        just('n').to('\n'),
+        just('t').to('\t'),
+        just('r').to('\r'),
+        just('0').to('\0'),
+        just('"'),
+        just('\\'),
+    )));
+
+    let unicode_escape = just("\\u")
+        .ignore_then(
+            text::digits(16)
+                .at_least(1)
+                .slice()
+                .delimited_by(just('{'), just('}')),
+        )
+        .validate(|digits: &str, span: SimpleSpan, emitter| {
+            let c = u32::from_str_radix(digits, 16)
+                .ok()
+                .filter(|_| digits.len() <= 6)
+                .and_then(char::from_u32);
+
+            match c {
+                Some(c) => c,
+                None => {
+                    emitter.emit(Rich::custom(
+                        span,
+                        format!("`{digits}` is not a valid unicode character"),
+                    ));
+                    char::REPLACEMENT_CHARACTER
+                }
+            }
+        });
+
+    let unknown_escape =
+        just('\\')
+            .ignore_then(any())
+            .validate(|c: char, span: SimpleSpan, emitter| {
+                emitter.emit(Rich::custom(
+                    span,
+                    format!("unknown character escape `\\{}`", c.escape_debug()),
+                ));
+                c
+            });
+
+    choice((simple_escape, unicode_escape, unknown_escape))
+}
+
+/// `"..."` with escape sequences, the string is allowed to span multiple lines as is
+fn quoted<'a>() -> impl Parser<'a, &'a str, String, Err<Rich<'a, char>>> + Clone {
+    choice((escape(), none_of("\\\"")))
+        .repeated()
+        .collect::<String>()
+        .delimited_by(just('"'), just('"'))
+}
+
+/// `r"..."` or `r#"..."#` with any number of `#`, nothing inside of them is escaped and they end at
+/// the first `"` followed by as many `#` as they started with
+fn raw<'a>() -> impl Parser<'a, &'a str, String, Err<Rich<'a, char>>> + Clone {
+    let closing = just('"').then(
+        just('#')
+            .repeated()
+            .configure(|cfg, hashes: &usize| cfg.exactly(*hashes))
+            .count(),
+    );
+
+    just('r')
+        .ignore_then(just('#').repeated().count())
+        .then_ignore(just('"'))
+        .then_with_ctx(
+            any()
+                .and_is(closing.not())
+                .repeated()
+                .slice()
+                .then_ignore(closing)
+                .map(str::to_string),
+        )
+}
+
+/// A multiline text block which starts with `"""` and a new line and ends with `"""` on a line of
+/// its own, the indentation of the closing `"""` is stripped from every line
+///
+/// ```text
+/// """
+///     Hello,
+///       world!
+///     """
+/// ```
+///
+/// is the same as `"Hello,\n  world!"`
+fn text_block<'a>() -> impl Parser<'a, &'a str, String, Err<Rich<'a, char>>> + Clone {
+    let delimiter = just("\"\"\"");
+
+    choice((
+        escape().map(|c| (c, false)),
+        none_of('\\').and_is(delimiter.not()).map(|c| (c, true)),
+    ))
+    .repeated()
+    .collect::<Vec<StringChar>>()
+    .delimited_by(delimiter.then(text::newline()), delimiter)
+    .validate(|chars, span: SimpleSpan, emitter| match dedent(&chars) {
+        Some(text) => text,
+        None => {
+            emitter.emit(Rich::custom(
+                span,
+                "the closing `\"\"\"` of a text block must be on a line of its own and every line \
+                 must be indented at least as much as it",
+            ));
+            chars.into_iter().map(|(c, _)| c).collect()
+        }
+    })
+}
+
+fn dedent(chars: &[StringChar]) -> Option<String> {
+    let mut lines = chars
+        .split(|&(c, literal)| literal && c == '\n')
+        .map(|line| match line {
+            [line @ .., ('\r', true)] => line,
+            line => line,
+        })
+        .collect::<Vec<_>>();
+
+    let indentation = lines.pop()?;
+    if !indentation
+        .iter()
+        .all(|&(c, literal)| literal && (c == ' ' || c == '\t'))
+    {
+        return None;
+    }
+
+    let lines = lines
+        .into_iter()
+        .map(|line| {
+            if line
+                .iter()
+                .all(|&(c, literal)| literal && c.is_whitespace())
+            {
+                Some(String::new())
+            } else {
+                line.strip_prefix(indentation)
+                    .map(|line| line.iter().map(|&(c, _)| c).collect::<String>())
+            }
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(lines.join("\n"))
+}
+
+pub(crate) fn string_literal<'a>() -> impl Parser<'a, &'a str, String, Err<Rich<'a, char>>> + Clone
+{
+    // Text blocks need to be tried first as their opening `"""` would otherwise be an empty string
+    // followed by the start of another one
+    choice((text_block(), quoted(), raw()))
+}
@@ -0,0 +1,128 @@
+use chumsky::{error::Rich, extra::Err, input::InputRef, primitive::custom, Parser};
+
+type Input<'a, 'parse> = InputRef<'a, 'parse, &'a str, Err<Rich<'a, char>>>;
+
+fn starts_with(inp: &mut Input, prefix: &str) -> bool {
+    let start = inp.save();
+    let matches = prefix.chars().all(|c| inp.next() == Some(c));
+    inp.rewind(start);
+
+    matches
+}
+
+fn skip_chars(inp: &mut Input, count: usize) {
+    (0..count).for_each(|_| inp.skip());
+}
+
+fn is_doc_comment(inp: &mut Input) -> bool {
+    // Like Rust, four or more slashes make a regular comment again
+    starts_with(inp, "///") && !starts_with(inp, "////")
+}
+
+fn skip_line(inp: &mut Input) {
+    while inp.peek().is_some_and(|c| c != '\n') {
+        inp.skip();
+    }
+}
+
+/// `/* ... */`, which can be nested so that commenting out code that has block comments in it
+/// works as expected
+///
+/// A comment that's never closed is left alone for [unterminated_comment] to report
+fn skip_block_comment(inp: &mut Input) {
+    let start = inp.save();
+    skip_chars(inp, 2);
+
+    let mut depth = 1;
+    while depth > 0 {
+        if starts_with(inp, "/*") {
+            skip_chars(inp, 2);
+            depth += 1;
+        } else if starts_with(inp, "*/") {
+            skip_chars(inp, 2);
+            depth -= 1;
+        } else if inp.next().is_none() {
+            inp.rewind(start);
+            return;
+        }
+    }
+}
+
+/// Stops at doc comments when they are kept and at block comments that are never closed
+///
+/// This is done by hand rather than with combinators as those would fill error messages with what
+/// a comment could start with instead of what the parser was expecting
+fn skip_trivia(inp: &mut Input, keep_doc_comments: bool) {
+    loop {
+        let before = inp.offset();
+
+        if inp.peek().is_some_and(char::is_whitespace) {
+            inp.skip();
+        } else if keep_doc_comments && is_doc_comment(inp) {
+            return;
+        } else if starts_with(inp, "//") {
+            skip_line(inp);
+        } else if starts_with(inp, "/*") {
+            skip_block_comment(inp);
+        }
+
+        if inp.offset() == before {
+            return;
+        }
+    }
+}
+
+/// Fails on a block comment that runs until the end of the file, which [skip_trivia] stops at
+fn unterminated_comment<'a>() -> impl Parser<'a, &'a str, (), Err<Rich<'a, char>>> + Clone {
+    custom(|inp: &mut Input<'a, '_>| {
+        if !starts_with(inp, "/*") {
+            return Ok(());
+        }
+
+        let start = inp.offset();
+        while inp.next().is_some() {}
+        Err(Rich::custom(
+            inp.span_since(start),
+            "unterminated block comment",
+        ))
+    })
+}
+
+/// Whitespace and comments, which are allowed between any two tokens
+pub(crate) fn trivia<'a>() -> impl Parser<'a, &'a str, (), Err<Rich<'a, char>>> + Clone {
+    custom(|inp: &mut Input<'a, '_>| {
+        skip_trivia(inp, false);
+        Ok(())
+    })
+    .then_ignore(unterminated_comment())
+}
+
+/// Like [trivia] but stops at doc comments so that they can be attached to the item after them
+pub(crate) fn item_trivia<'a>() -> impl Parser<'a, &'a str, (), Err<Rich<'a, char>>> + Clone {
+    custom(|inp: &mut Input<'a, '_>| {
+        skip_trivia(inp, true);
+        Ok(())
+    })
+    .then_ignore(unterminated_comment())
+}
+
+/// The `///` comments before an item, with one line of the string per line of comment
+pub(crate) fn doc_comments<'a>(
+) -> impl Parser<'a, &'a str, Option<String>, Err<Rich<'a, char>>> + Clone {
+    custom(|inp: &mut Input<'a, '_>| {
+        let mut lines = Vec::new();
+        while is_doc_comment(inp) {
+            skip_chars(inp, 3);
+            let start = inp.offset();
+            skip_line(inp);
+
+            let line: &str = inp.slice(start..inp.offset());
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            lines.push(line.strip_prefix(' ').unwrap_or(line));
+
+            skip_trivia(inp, true);
+        }
+
+        Ok((!lines.is_empty()).then(|| lines.join("\n")))
+    })
+}
@@ -0,0 +1,22 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(block_expressions; r#"
+
+fn main() -> Html {
+    let title = {
+        let greeting = "Hello";
+        greeting
+    };
+    {
+        log(title);
+    }
+    {
+        Html {
+            Paragraph({ title })
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,25 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(comments; r#"
+// A page with a greeting
+
+/// Shows a greeting
+///
+///   with an indented line
+//// Four slashes are a regular comment
+fn greeting(/* who */ name: String) -> Html {
+    Paragraph(name) // trailing comment
+}
+
+/* Block comments /* can be nested */
+   and span lines */
+fn main() -> Html {
+    let name /* : String */ = "world";
+    Html {
+        // The only child
+        greeting(name), /// not attached to anything
+    }
+}
+"#);
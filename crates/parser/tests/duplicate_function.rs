@@ -0,0 +1,15 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(duplicate_function; r#"
+
+fn main() -> Html {
+    Html {}
+}
+
+fn main() -> String {
+    "I was here first!"
+}
+
+"#);
@@ -0,0 +1,18 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(effect_rows; r#"
+
+fn load() -> Html eff Fetch {
+    get("/posts");
+    Html {}
+}
+
+fn main() -> Html eff std::console::Console + Fetch
+    + Console {
+    log("loading");
+    load()
+}
+
+"#);
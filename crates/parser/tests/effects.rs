@@ -0,0 +1,20 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(effects; r#"
+
+/// Keeps track of what visitors do
+effect Analytics {
+    /// Records that something happened
+    fn track(event: String, count: Int) -> ();
+    fn flush() -> Bool;
+    fn track(event: String) -> ( );
+}
+
+fn main() -> Html eff Analytics {
+    Analytics::track("visit", 1);
+    Html {}
+}
+
+"#);
@@ -0,0 +1,25 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(error_recovery; r#"
+
+fn main() -> Html {
+    log(#);
+    log("fine");
+    Html {
+        Body {
+            Paragraph("hi" "there")
+        }
+    }
+}
+
+fn broken( -> Html {
+    Html {}
+}
+
+fn footer() -> Html {
+    Paragraph("bye")
+}
+
+"#);
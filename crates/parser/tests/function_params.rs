@@ -0,0 +1,20 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(function_params; r#"
+
+fn card(title: String, body: Html,) -> Html {
+    Body {
+        Paragraph(title),
+        body
+    }
+}
+
+fn main() -> Html {
+    Html {
+        card("Hello", Paragraph("world"))
+    }
+}
+
+"#);
@@ -0,0 +1,16 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(generics; r#"
+
+fn first<T>(xs: List<T>) -> Option<T> {
+    first(xs)
+}
+
+fn pairs < K, V, >(keys: List<K>, values: std::list::List< List<V>, >) -> Html {
+    let names: Option<List<String>> = "none";
+    Html {}
+}
+
+"#);
@@ -0,0 +1,18 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(handlers; r#"
+
+fn main() -> Html eff Console {
+    let name = handle {
+        Prompt::ask("Name?")
+    } with Prompt {
+        ask(question) => resume("Ada"),
+        cancel() => { "nobody" },
+    };
+    handle { greet(name); } with crate::Logger { log(msg) => resume(()) }
+    Html {}
+}
+
+"#);
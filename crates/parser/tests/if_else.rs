@@ -0,0 +1,21 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(if_else; r#"
+
+fn main() -> Html {
+    let signed_in = true;
+    if signed_in {
+        log("welcome back");
+    } else if is_admin(signed_in) {
+        log("hello admin");
+    } else {
+        log("please sign in");
+    }
+    Html {
+        if signed_in { Paragraph("Hi!") } else { Paragraph("Who are you?") }
+    }
+}
+
+"#);
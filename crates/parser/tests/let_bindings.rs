@@ -0,0 +1,15 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(let_bindings; r#"
+
+fn main() -> Html {
+    let greeting = "Hello";
+    let greeting: String = greeting;
+    Html {
+        Paragraph(greeting)
+    }
+}
+
+"#);
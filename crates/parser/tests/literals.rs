@@ -0,0 +1,15 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(literals; r#"
+
+fn main() -> Html {
+    log(42, -7, 1_000_000);
+    log(3.14, -0.5, 6.02e23, 1E-9);
+    log(true, false, trueish);
+    log(99999999999999999999);
+    Html {}
+}
+
+"#);
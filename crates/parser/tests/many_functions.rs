@@ -0,0 +1,24 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(many_functions; r#"
+
+fn header() -> Html {
+    Paragraph("Welcome!")
+}
+
+fn main() -> Html {
+    Html {
+        Body {
+            header,
+            footer
+        }
+    }
+}
+
+fn footer() -> Html {
+    Paragraph("Goodbye!")
+}
+
+"#);
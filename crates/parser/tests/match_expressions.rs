@@ -0,0 +1,22 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(match_expressions; r#"
+
+fn main() -> Html {
+    let count = 2;
+    match count {
+        0 => log("none"),
+        n => { log("some"); },
+    }
+    Html {
+        match is_admin(count) {
+            true => Paragraph("Admin"),
+            Some(_, "guest",) => Paragraph("Guest"),
+            _ => Paragraph("User")
+        }
+    }
+}
+
+"#);
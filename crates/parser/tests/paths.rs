@@ -0,0 +1,18 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(paths; r#"
+
+fn Paragraph(text: std::primitive::String) -> std::html::Html {
+    std::html::Paragraph(text)
+}
+
+fn main() -> Html eff std::console::Console {
+    std::console::log("hi");
+    Html {
+        crate :: Paragraph("Hello")
+    }
+}
+
+"#);
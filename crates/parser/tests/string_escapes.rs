@@ -0,0 +1,22 @@
+mod setup;
+
+#[rustfmt::skip::macros(setup)]
+
+setup!(string_escapes; r##"
+
+fn main() -> Html {
+    log("tab\tquote\"backslash\\newline\nheart\u{2764}");
+    log(r"C:\raw\path");
+    log(r#"she said "hi""#);
+    log("two
+lines");
+    log("""
+        Hello,
+          world!
+
+        """);
+    log("bad \q escape and \u{110000}");
+    Html {}
+}
+
+"##);
@@ -0,0 +1,199 @@
+use std::{fmt::Write, str::FromStr};
+
+use generator::Generator;
+use hugs::{
+    call_graph, generate_call_graph,
+    hir::{Hir, Id},
+};
+use parser::AST;
+
+use crate::Compiled;
+
+/// One of the stages of compiling a file that can be written out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Emit {
+    /// The AST as JSON
+    Ast,
+    /// The HIR printed back out as code
+    Hir,
+    /// Which function calls which, as a Graphviz graph
+    CallGraphDot,
+    /// Which function calls which, as JSON
+    CallGraphJson,
+    /// Only the JS that goes in the page
+    Js,
+    /// The page itself, which is what's written when nothing else is asked for
+    Html,
+}
+
+/// How far a file has to get through compiling before an output can be written, in the order the
+/// stages happen in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Stage {
+    Parsed,
+    /// Lowered into HIR, which happens even when some names couldn't be resolved
+    Lowered,
+    /// Checked and rendered into a page
+    Compiled,
+}
+
+impl Emit {
+    pub const ALL: [Emit; 6] = [
+        Emit::Ast,
+        Emit::Hir,
+        Emit::CallGraphDot,
+        Emit::CallGraphJson,
+        Emit::Js,
+        Emit::Html,
+    ];
+
+    /// What the output is called on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Emit::Ast => "ast",
+            Emit::Hir => "hir",
+            Emit::CallGraphDot => "call-graph-dot",
+            Emit::CallGraphJson => "call-graph-json",
+            Emit::Js => "js",
+            Emit::Html => "html",
+        }
+    }
+
+    /// How far the file has to get through compiling before this can be written
+    pub fn stage(self) -> Stage {
+        match self {
+            Emit::Ast => Stage::Parsed,
+            Emit::Hir | Emit::CallGraphDot | Emit::CallGraphJson => Stage::Lowered,
+            Emit::Js | Emit::Html => Stage::Compiled,
+        }
+    }
+
+    /// Writes out an output that only needs the file to have been parsed
+    pub fn from_ast(self, ast: &AST) -> String {
+        match self {
+            Emit::Ast => {
+                let mut json = serde_json::to_string_pretty(ast)
+                    .expect("the AST to only contain things JSON can represent");
+                json.push('\n');
+                json
+            }
+            _ => panic!("`{}` can't be written from the AST alone", self.name()),
+        }
+    }
+
+    /// Writes out an output that only needs the file to have been lowered
+    pub fn from_hir(self, hir: &Hir) -> String {
+        match self {
+            Emit::Hir => hugs::pretty::print(hir),
+            Emit::CallGraphDot => call_graph_dot(hir),
+            Emit::CallGraphJson => {
+                let mut json = serde_json::to_string_pretty(&call_graph_json(hir))
+                    .expect("the call graph to only contain strings");
+                json.push('\n');
+                json
+            }
+            _ => panic!("`{}` can't be written from the HIR alone", self.name()),
+        }
+    }
+
+    /// The extension of the files the output is usually written to
+    pub fn extension(self) -> &'static str {
+        match self {
+            Emit::Ast => "ast",
+            Emit::Hir => "hir",
+            Emit::CallGraphDot => "dot",
+            Emit::CallGraphJson => "json",
+            Emit::Js => "js",
+            Emit::Html => "html",
+        }
+    }
+}
+
+impl FromStr for Emit {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Emit::ALL
+            .into_iter()
+            .find(|emit| emit.name() == name)
+            .ok_or_else(|| {
+                let names = Emit::ALL.map(Emit::name);
+                format!(
+                    "unknown output `{name}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl Compiled {
+    /// Writes out one of the stages the file went through
+    pub fn emit(&self, emit: Emit) -> String {
+        match emit {
+            Emit::Ast => emit.from_ast(&self.ast),
+            Emit::Hir | Emit::CallGraphDot | Emit::CallGraphJson => emit.from_hir(&self.hir),
+            Emit::Js => Generator::new().generate_js(&self.hir),
+            Emit::Html => format!("{}\n", self.html),
+        }
+    }
+}
+
+fn name(hir: &Hir, id: Id) -> &str {
+    hir.id_map.get(&id).map_or("{unknown}", |name| name)
+}
+
+/// Functions that `main` never calls are dashed and ones that are recursive are bold
+fn call_graph_dot(hir: &Hir) -> String {
+    let graph = generate_call_graph(hir);
+    let reachable = call_graph::reachable(hir, &graph);
+    let recursive = call_graph::recursive(hir, &graph).concat();
+
+    let mut dot = String::from("digraph {\n");
+    for id in graph.nodes() {
+        let mut attributes = vec![format!("label={:?}", name(hir, id))];
+        if hir.module.contains_key(&id) && !reachable.contains(&id) {
+            attributes.push("style=dashed".to_string());
+        }
+        if recursive.contains(&id) {
+            attributes.push("penwidth=2".to_string());
+        }
+        writeln!(dot, "    \"{id}\" [{}];", attributes.join(", ")).unwrap();
+    }
+    for (from, to, ()) in graph.all_edges() {
+        writeln!(dot, "    \"{from}\" -> \"{to}\";").unwrap();
+    }
+    dot.push_str("}\n");
+
+    dot
+}
+
+fn call_graph_json(hir: &Hir) -> serde_json::Value {
+    let graph = generate_call_graph(hir);
+    let reachable = call_graph::reachable(hir, &graph);
+    let unreachable = hir
+        .module
+        .keys()
+        .filter(|id| graph.contains_node(**id) && !reachable.contains(id))
+        .map(Id::to_string)
+        .collect::<Vec<_>>();
+    let recursive = call_graph::recursive(hir, &graph)
+        .into_iter()
+        .map(|group| group.iter().map(Id::to_string).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "nodes": graph
+            .nodes()
+            .map(|id| serde_json::json!({ "id": id.to_string(), "name": name(hir, id) }))
+            .collect::<Vec<_>>(),
+        "edges": graph
+            .all_edges()
+            .map(|(from, to, ())| serde_json::json!({
+                "from": from.to_string(),
+                "to": to.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+        "unreachable": unreachable,
+        "recursive": recursive,
+    })
+}
@@ -1,11 +1,97 @@
+mod emit;
+
+use diagnostics::{codes, Diagnostic};
 use generator::Generator;
-use parser::Parser;
+use hugs::hir::Hir;
+use parser::{LineIndex, ModuleItem, Parser, Span, AST};
+
+pub use emit::{Emit, Stage};
+
+/// The page a file compiled to along with the warnings found while compiling it, and what it went
+/// through on the way there
+pub struct Compiled {
+    pub ast: AST,
+    pub hir: Hir,
+    pub html: String,
+    pub warnings: Vec<Diagnostic>,
+}
 
-pub fn compile(input: &str) -> String {
+/// Parses a file into as much of an AST as could be recovered from its syntax errors, along with
+/// every one of them
+pub fn parse(input: &str) -> (Option<AST>, Vec<Diagnostic>) {
     let parser = Parser::new();
-    // TODO: Handle parser errors properly
-    let ast = parser.parse(input).unwrap();
+    let (ast, errors) = parser.parse(input).into_output_errors();
+
+    (
+        ast,
+        errors.iter().map(Diagnostic::from_parse_error).collect(),
+    )
+}
+
+/// Lowers a parsed file into HIR along with the diagnostics found doing so, names that couldn't be
+/// resolved are left as placeholders so the HIR is there even when some of them are errors
+pub fn lower(ast: &AST) -> (Hir, Vec<Diagnostic>) {
+    Hir::lower(ast)
+}
+
+/// Checks a lowered file and renders it into a page, `diagnostics` being the ones found lowering
+/// it, failing with every diagnostic found if any of them are errors
+pub fn generate(
+    input: &str,
+    ast: AST,
+    hir: Hir,
+    mut diagnostics: Vec<Diagnostic>,
+) -> Result<Compiled, Vec<Diagnostic>> {
+    if !matches!(ast.module.get("main"), Some(ModuleItem::Function(_))) {
+        return Err(vec![Diagnostic::error(
+            codes::MISSING_MAIN,
+            Span::new(input.len(), input.len()),
+            "`main` function not found",
+        )
+        .with_help("add a `fn main() -> Html { ... }` that returns the page")]);
+    }
+
+    diagnostics.extend(hugs::typeck::check(&hir));
+    diagnostics.extend(hugs::effects::check(&hir));
+    diagnostics.extend(hugs::call_graph::check(&hir));
+    if diagnostics.iter().any(Diagnostic::is_error) {
+        return Err(diagnostics);
+    }
+
     let generator = Generator::new();
+    let html = match generator.generate(&hir) {
+        Ok(html) => html,
+        Err(errors) => {
+            diagnostics.extend(errors);
+            return Err(diagnostics);
+        }
+    };
+
+    Ok(Compiled {
+        html,
+        ast,
+        hir,
+        warnings: diagnostics,
+    })
+}
+
+/// Compiles a file into a page, failing with every diagnostic found if any of them are errors
+pub fn compile(input: &str) -> Result<Compiled, Vec<Diagnostic>> {
+    let ast = match parse(input) {
+        (Some(ast), errors) if errors.is_empty() => ast,
+        (_, errors) => return Err(errors),
+    };
+    let (hir, diagnostics) = lower(&ast);
+    generate(input, ast, hir, diagnostics)
+}
+
+/// Renders every diagnostic one after another the way they are shown in the terminal
+pub fn render_diagnostics(file_name: &str, input: &str, diagnostics: &[Diagnostic]) -> String {
+    let index = LineIndex::new(input);
 
-    generator.generate(&ast)
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.render(file_name, &index))
+        .collect::<Vec<_>>()
+        .join("\n")
 }
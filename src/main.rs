@@ -1,6 +1,78 @@
-use std::io::Read;
+use std::{io::Read, path::PathBuf};
+
+use diagnostics::Diagnostic;
+use effectful::{Emit, Stage};
+
+const USAGE: &str = "\
+Compiles the effectful program read from stdin
+
+Usage: effectful [--emit <output>[=<path>]]...
+
+Options:
+  --emit <output>[=<path>]  What to write out, to stdout unless a path is given, this can be passed
+                            more than once and defaults to `html`, the outputs are: ast, hir,
+                            call-graph-dot, call-graph-json, js and html, the ones before js
+                            are written even when the file fails to compile later on
+  -h, --help                Print this help
+";
+
+/// An output that was asked for and where it goes, `None` being stdout
+struct Output {
+    emit: Emit,
+    path: Option<PathBuf>,
+}
+
+impl Output {
+    fn parse(arg: &str) -> Result<Self, String> {
+        let (emit, path) = match arg.split_once('=') {
+            Some((emit, path)) => (emit, Some(PathBuf::from(path))),
+            None => (arg, None),
+        };
+
+        Ok(Output {
+            emit: emit.parse()?,
+            path,
+        })
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Vec<Output>, String> {
+    let mut outputs = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                std::process::exit(0);
+            }
+            "--emit" => {
+                let output = args.next().ok_or("`--emit` expects an output")?;
+                outputs.push(Output::parse(&output)?);
+            }
+            _ => match arg.strip_prefix("--emit=") {
+                Some(output) => outputs.push(Output::parse(output)?),
+                None => return Err(format!("unexpected argument `{arg}`")),
+            },
+        }
+    }
+
+    if outputs.is_empty() {
+        outputs.push(Output {
+            emit: Emit::Html,
+            path: None,
+        });
+    }
+    Ok(outputs)
+}
 
 fn main() {
+    let outputs = match parse_args(std::env::args().skip(1)) {
+        Ok(outputs) => outputs,
+        Err(error) => {
+            eprint!("error: {error}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+
     let mut buffer = Vec::new();
     let stdin = std::io::stdin();
     let mut handle = stdin.lock();
@@ -10,6 +82,72 @@ fn main() {
 
     let input = std::str::from_utf8(&buffer).expect("valid utf8 input");
 
-    let output = effectful::compile(input);
-    println!("{output}");
+    // Outputs are written as soon as the stage they need is done, so that the earlier stages can
+    // still be looked at when a later one fails
+    let last_stage = outputs
+        .iter()
+        .map(|output| output.emit.stage())
+        .max()
+        .expect("there to always be an output");
+
+    // The AST is written even when it's only what could be recovered from the syntax errors
+    let (ast, errors) = effectful::parse(input);
+    if let Some(ast) = &ast {
+        write_outputs(&outputs, Stage::Parsed, |emit| emit.from_ast(ast));
+    }
+    let ast = match ast {
+        Some(ast) if errors.is_empty() => ast,
+        _ => fail(input, &errors),
+    };
+    if last_stage == Stage::Parsed {
+        return;
+    }
+
+    let (hir, diagnostics) = effectful::lower(&ast);
+    write_outputs(&outputs, Stage::Lowered, |emit| emit.from_hir(&hir));
+    if last_stage == Stage::Lowered {
+        eprint!(
+            "{}",
+            effectful::render_diagnostics("<stdin>", input, &diagnostics)
+        );
+        if diagnostics.iter().any(Diagnostic::is_error) {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    match effectful::generate(input, ast, hir, diagnostics) {
+        Ok(compiled) => {
+            eprint!(
+                "{}",
+                effectful::render_diagnostics("<stdin>", input, &compiled.warnings)
+            );
+            write_outputs(&outputs, Stage::Compiled, |emit| compiled.emit(emit));
+        }
+        Err(diagnostics) => fail(input, &diagnostics),
+    }
+}
+
+/// Writes every output that needs exactly the given stage, using `emit` to write each of them
+fn write_outputs(outputs: &[Output], stage: Stage, emit: impl Fn(Emit) -> String) {
+    for output in outputs.iter().filter(|output| output.emit.stage() == stage) {
+        let emitted = emit(output.emit);
+        match &output.path {
+            Some(path) => {
+                if let Err(error) = std::fs::write(path, emitted) {
+                    eprintln!("error: unable to write to {}: {error}", path.display());
+                    std::process::exit(1);
+                }
+            }
+            None => print!("{emitted}"),
+        }
+    }
+}
+
+fn fail(input: &str, diagnostics: &[Diagnostic]) -> ! {
+    eprint!(
+        "{}",
+        effectful::render_diagnostics("<stdin>", input, diagnostics)
+    );
+    std::process::exit(1);
 }
@@ -0,0 +1,19 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(argument_types; r#"
+
+fn card(title: String, featured: Bool) -> Html {
+    Paragraph(title)
+}
+
+fn main() -> Html {
+    Html {
+        Body {
+            card("Welcome", 1),
+            card("Hello"),
+            card
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,24 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(builtin_arguments; r#"
+
+fn card(title: String) -> Html {
+    Paragraph(title)
+}
+
+fn main() -> Html {
+    Html("page") {
+        Body("x") {
+            Paragraph("a", "b"),
+            Paragraph(),
+            Paragraph("c") {
+                "d"
+            },
+            card("e") {
+                Paragraph("f")
+            }
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,29 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(constructor_patterns; r#"
+
+fn greet(name: String) -> String {
+    name
+}
+
+fn describe(animal: Option<String>) -> String {
+    match animal {
+        Some(1) => "one",
+        Some(name, "extra") => name,
+        Somme(name) => name,
+        greet(name) => name,
+        _ => "nobody",
+    }
+}
+
+fn main() -> Html {
+    let name = "Ferris";
+    Html {
+        match name {
+            None => Paragraph("nobody"),
+            _ => Paragraph(describe(None)),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,11 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(dangling_doc_comment; r#"
+
+fn main() -> Html {
+    Html {}
+}
+
+/// Renders the footer of every page
+
+"#);
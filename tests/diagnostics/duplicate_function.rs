@@ -0,0 +1,13 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(duplicate_function; r#"
+
+fn main() -> Html {
+    Html {}
+}
+
+fn main() -> Html {
+    Html {}
+}
+
+"#);
@@ -0,0 +1,21 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(generic_annotations; r#"
+
+fn main() -> Html {
+    let maybe: Option<Bool> = "s";
+    let items: List<Int> = 5;
+    Html {
+        Body {
+            if maybe {
+                Paragraph("yes")
+            },
+            match items {
+                "a" => Paragraph("a"),
+                _ => Paragraph("other"),
+            }
+        }
+    }
+}
+
+"#);
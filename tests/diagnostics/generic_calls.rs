@@ -0,0 +1,18 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(generic_calls; r#"
+
+fn id<T>(value: T) -> T {
+    value
+}
+
+fn first<T>(a: T, b: T) -> T {
+    "first"
+}
+
+fn main() -> Html {
+    let pair = first(1, "two");
+    id("page")
+}
+
+"#);
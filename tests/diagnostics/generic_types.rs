@@ -0,0 +1,23 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(generic_types; r#"
+
+fn names() -> List<String> {
+    5
+}
+
+fn first<T>(items: List<T>) -> T {
+    first(items)
+}
+
+fn count(items: List<Int>) -> Int {
+    let name: String = first(items);
+    let items: List<String> = "items";
+    0
+}
+
+fn main() -> List<String> {
+    "hi"
+}
+
+"#);
@@ -0,0 +1,23 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(incomplete_handler; r#"
+
+effect Prompt {
+    fn ask(question: String) -> String;
+    fn confirm(question: String) -> Bool;
+    fn cancel() -> ();
+}
+
+fn main() -> Html {
+    let name = handle {
+        Prompt::ask("Name?")
+    } with Prompt {
+        ask(question, default) => resume(42),
+    };
+    let count = handle {
+        1
+    } with Html {};
+    Html {}
+}
+
+"#);
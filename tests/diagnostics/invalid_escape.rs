@@ -0,0 +1,13 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(invalid_escape; r#"
+
+fn main() -> Html {
+    Html {
+        Body {
+            Paragraph("C:\path\to\file \u{D800}")
+        }
+    }
+}
+
+"#);
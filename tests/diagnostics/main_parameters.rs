@@ -0,0 +1,13 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(main_parameters; r#"
+
+fn main<T>(name: String, greeting: T) -> Html {
+    Html {
+        Body {
+            Paragraph(name)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,18 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(many_errors; r#"
+
+fn main() -> Html {
+    log(#);
+    Html {
+        Body {
+            Paragraph("hi" "there")
+        }
+    }
+}
+
+fn broken( -> Html {
+    Html {}
+}
+
+"#);
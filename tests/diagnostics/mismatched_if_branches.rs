@@ -0,0 +1,17 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(mismatched_if_branches; r#"
+
+fn main() -> Html {
+    let signed_in = true;
+    let label = if signed_in {
+        "Welcome back!"
+    } else {
+        404
+    };
+    Html {
+        Paragraph(label)
+    }
+}
+
+"#);
@@ -0,0 +1,16 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(mismatched_patterns; r#"
+
+fn main() -> Html {
+    let name = "Ferris";
+    Html {
+        match name {
+            true => Paragraph("True"),
+            0 => Paragraph("Zero"),
+            _ => Paragraph(name),
+        }
+    }
+}
+
+"#);
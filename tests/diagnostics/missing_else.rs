@@ -0,0 +1,18 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(missing_else; r#"
+
+fn greeting(formal: Bool) -> String eff Console {
+    let greeting = if formal { "Good evening" };
+    if formal { log(greeting) };
+    "Hi"
+}
+
+fn main() -> Html eff Console {
+    log(greeting(true));
+    if false {
+        Html {}
+    }
+}
+
+"#);
@@ -0,0 +1,9 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(missing_main; r#"
+
+fn page() -> Html {
+    Html {}
+}
+
+"#);
@@ -0,0 +1,42 @@
+pub mod argument_types;
+pub mod builtin_arguments;
+pub mod constructor_patterns;
+pub mod dangling_doc_comment;
+pub mod duplicate_function;
+pub mod generic_annotations;
+pub mod generic_calls;
+pub mod generic_types;
+pub mod incomplete_handler;
+pub mod invalid_escape;
+pub mod main_parameters;
+pub mod many_errors;
+pub mod mismatched_if_branches;
+pub mod mismatched_patterns;
+pub mod missing_arrow;
+pub mod missing_else;
+pub mod missing_main;
+pub mod non_bool_condition;
+pub mod non_exhaustive_constructors;
+pub mod non_exhaustive_match;
+pub mod not_a_function;
+pub mod not_a_type;
+pub mod not_an_effect;
+pub mod operation_arguments;
+pub mod operation_output;
+pub mod paragraph_text;
+pub mod reserved_keywords;
+pub mod resume_as_value;
+pub mod return_types;
+pub mod runtime_fields;
+pub mod runtime_values;
+pub mod std_operation_arguments;
+pub mod transitive_effects;
+pub mod undeclared_effect;
+pub mod unhandled_effect;
+pub mod unknown_constructor;
+pub mod unreachable_function;
+pub mod unreachable_pattern;
+pub mod unresolved_names;
+pub mod unterminated_comment;
+pub mod unused_effect;
+pub mod wrong_number_of_type_arguments;
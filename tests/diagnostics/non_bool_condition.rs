@@ -0,0 +1,12 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(non_bool_condition; r#"
+
+fn main() -> Html {
+    let count = 1;
+    Html {
+        if count { Paragraph("Some") } else { Paragraph("None") }
+    }
+}
+
+"#);
@@ -0,0 +1,32 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(non_exhaustive_constructors; r#"
+
+fn describe(animal: Option<String>) -> String {
+    match animal {
+        Some(name) => name,
+    }
+}
+
+fn check(answer: Option<Option<Bool>>) -> String {
+    match answer {
+        Some(Some(true)) => "yes",
+        None => "no answer",
+    }
+}
+
+fn main() -> Html {
+    let answer = Some(Some(false));
+    Html {
+        Paragraph(describe(None)),
+        Paragraph(check(answer)),
+        match answer {
+            Some(Some(_)) => Paragraph("answered"),
+            Some(_) => Paragraph("skipped"),
+            None => Paragraph("no answer"),
+            Some(None) => Paragraph("again"),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,21 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(non_exhaustive_match; r#"
+
+fn label(count: Int) -> String {
+    match count {
+        0 => "none",
+        1 => "one",
+    }
+}
+
+fn main() -> Html {
+    let signed_in = true;
+    Html {
+        match signed_in {
+            true => Paragraph(label(1)),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,25 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(not_a_function; r#"
+
+effect Logger {
+    fn log(message: String) -> ();
+}
+
+fn greet(greeting: String) -> String {
+    greeting()
+}
+
+fn main() -> Html {
+    let name = String("Ferris");
+    let logger = Logger();
+    let header = "Hello";
+    header();
+    Html {
+        Body {
+            Paragraph(greet(name)),
+        }
+    }
+}
+
+"#);
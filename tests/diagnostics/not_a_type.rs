@@ -0,0 +1,14 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(not_a_type; r#"
+
+fn greeting(name: String) -> Paragraph {
+    let copy: name = name;
+    Paragraph(copy)
+}
+
+fn main() -> List<Console> {
+    Html {}
+}
+
+"#);
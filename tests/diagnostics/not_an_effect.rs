@@ -0,0 +1,10 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(not_an_effect; r#"
+
+fn main() -> Html eff Console + Html + log {
+    log("hi");
+    Html {}
+}
+
+"#);
@@ -0,0 +1,21 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(operation_arguments; r#"
+
+effect Analytics {
+    fn track(event: String, count: Int) -> ();
+    fn enabled() -> Bool;
+}
+
+fn visit() -> Html eff Analytics {
+    track("visit");
+    track(1, "visit");
+    let enabled: Bool = enabled();
+    if enabled() { Html {} } else { Html {} }
+}
+
+fn main() -> Html {
+    Html {}
+}
+
+"#);
@@ -0,0 +1,19 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(operation_output; r#"
+
+effect Counter {
+    fn next() -> Int;
+}
+
+fn main() -> Html eff Dialog {
+    let name: Int = prompt("Name?");
+    let count: String = handle {
+        Counter::next()
+    } with Counter {
+        next() => resume("one"),
+    };
+    Html {}
+}
+
+"#);
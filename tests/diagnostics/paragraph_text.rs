@@ -0,0 +1,16 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(paragraph_text; r#"
+
+fn main() -> Html {
+    let page = Html {};
+    Html {
+        Body {
+            Paragraph(page),
+            Paragraph(()),
+            Paragraph(3),
+        }
+    }
+}
+
+"#);
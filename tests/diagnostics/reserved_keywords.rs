@@ -0,0 +1,12 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(reserved_keywords; r#"
+
+fn match() -> () {}
+
+fn main() -> Html {
+    let if = "hidden";
+    Html {}
+}
+
+"#);
@@ -0,0 +1,22 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(resume_as_value; r#"
+
+effect Greeter {
+    fn greet() -> ();
+}
+
+fn main() -> Html {
+    handle {
+        Greeter::greet();
+        Html {}
+    } with Greeter {
+        greet() => Html {
+            Body {
+                Paragraph(resume)
+            }
+        },
+    }
+}
+
+"#);
@@ -0,0 +1,17 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(return_types; r#"
+
+fn title() -> String {
+    Paragraph("Title")
+}
+
+fn count() -> Int {
+    let count = 1;
+}
+
+fn main() -> String {
+    title()
+}
+
+"#);
@@ -0,0 +1,16 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(runtime_fields; r#"
+
+fn main() -> Html eff Dialog {
+    let answer = Some(prompt("Are you a crab?"));
+    Html {
+        match answer {
+            None => Paragraph("no answer"),
+            Some("yes") => Paragraph("a crab"),
+            Some(_) => Paragraph("not a crab"),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,47 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(runtime_values; r#"
+
+effect Ask {
+    fn ask() -> Bool;
+}
+
+fn confirm(question: String) -> Bool eff Dialog {
+    match prompt(question) {
+        "y" => true,
+        "n" => false,
+        _ => confirm(question),
+    }
+}
+
+fn main() -> Html eff Dialog {
+    let name = prompt("Name?");
+    let greeting = match prompt("Greeting?") {
+        "" => "Hello",
+        greeting => greeting,
+    };
+    let shown = handle {
+        if ask() {
+            "Shown"
+        } else {
+            "Hidden"
+        }
+    } with Ask {
+        ask() => resume(confirm("Show?")),
+    };
+    // Bound but never compared to anything, so what it is doesn't matter while rendering
+    let answer = match prompt("Anything?") {
+        answer => "Thanks",
+    };
+
+    Html {
+        Body {
+            Paragraph(name),
+            Paragraph(greeting),
+            Paragraph(shown),
+            Paragraph(answer)
+        }
+    }
+}
+
+"#);
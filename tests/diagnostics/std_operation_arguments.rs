@@ -0,0 +1,12 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(std_operation_arguments; r#"
+
+fn main() -> Html eff Console + Dialog {
+    log();
+    log("a", "b");
+    let answer: String = prompt(5);
+    Html {}
+}
+
+"#);
@@ -0,0 +1,40 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(transitive_effects; r#"
+
+effect Logger {
+    fn log(msg: String) -> ();
+}
+
+// Calls itself, what it performs still reaches whatever calls it
+fn countdown(count: Int) -> () {
+    Logger::log("tick");
+    match count {
+        0 => (),
+        count => countdown(count),
+    }
+}
+
+fn announce() -> () {
+    countdown(1)
+}
+
+fn wrapper() -> () eff Logger {
+    countdown(3)
+}
+
+fn silenced() -> () {
+    handle {
+        wrapper()
+    } with Logger {
+        log(msg) => resume(()),
+    }
+}
+
+fn main() -> Html eff Console {
+    std::console::log("start");
+    silenced();
+    Html {}
+}
+
+"#);
@@ -0,0 +1,33 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(undeclared_effect; r#"
+
+effect Logger {
+    fn log(msg: String) -> ();
+}
+
+fn greet(name: String) -> () eff Logger {
+    Logger::log(name);
+}
+
+fn welcome() -> Html {
+    std::console::log("welcome");
+    greet("Ada");
+    Paragraph("Welcome")
+}
+
+fn main() -> Html eff Console {
+    handle {
+        greet("Ada");
+        std::console::log("handled blocks still perform other effects");
+    } with Logger {
+        log(msg) => resume(()),
+    }
+    Html {
+        Body {
+            welcome
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,14 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(unhandled_effect; r#"
+
+effect Analytics {
+    fn track(event: String) -> ();
+}
+
+fn main() -> Html eff Console + Analytics {
+    track("visit");
+    Html {}
+}
+
+"#);
@@ -0,0 +1,16 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(unknown_constructor; r#"
+
+fn main() -> Html {
+    let name = "Ferris";
+    Html {
+        match name {
+            Paragraph(_) => Paragraph("Nested"),
+            0 => Paragraph("Zero"),
+            _ => Paragraph(name),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,31 @@
+#[rustfmt::skip::macros(setup_warnings)]
+
+crate::setup_warnings!(unreachable_function; r#"
+
+fn even(n: Int) -> Bool {
+    match n {
+        0 => true,
+        _ => odd(n),
+    }
+}
+
+fn odd(n: Int) -> Bool {
+    match n {
+        0 => false,
+        _ => even(n),
+    }
+}
+
+fn greeting() -> String {
+    "Hello"
+}
+
+fn main() -> Html {
+    Html {
+        Body {
+            Paragraph(greeting()),
+        }
+    }
+}
+
+"#);
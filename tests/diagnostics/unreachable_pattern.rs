@@ -0,0 +1,24 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(unreachable_pattern; r#"
+
+fn label(count: Int) -> String {
+    match count {
+        0 => "none",
+        n => "some",
+        1 => "one",
+    }
+}
+
+fn main() -> Html {
+    let signed_in = true;
+    Html {
+        match signed_in {
+            true => Paragraph(label(1)),
+            false => Paragraph("Sign in"),
+            true => Paragraph("Again"),
+        }
+    }
+}
+
+"#);
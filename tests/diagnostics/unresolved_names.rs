@@ -0,0 +1,33 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(unresolved_names; r#"
+
+effect Logger {
+    fn log(message: String) -> ();
+}
+
+fn greeting(name: Strng) -> Html eff Logr {
+    Logger::lgo(nmae);
+    Paragrap(name)
+}
+
+fn main() -> Html {
+    let page = handle {
+        greeting("Ferris")
+    } with Loger {
+        log(message) => resume(()),
+    };
+    let other = handle {
+        greeting("Corro")
+    } with Logger {
+        lg(message) => resume(()),
+    };
+    Html {
+        Body {
+            std::html::Paragrph("Hello"),
+            frobnicate(),
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,10 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(unterminated_comment; r#"
+
+fn main() -> Html {
+    /* Hidden for now /* nested */
+    Html {}
+}
+
+"#);
@@ -0,0 +1,25 @@
+#[rustfmt::skip::macros(setup_warnings)]
+
+crate::setup_warnings!(unused_effect; r#"
+
+effect Analytics {
+    fn track(event: String) -> ();
+}
+
+fn quiet() -> Html eff Analytics + Console {
+    Paragraph("Shh")
+}
+
+fn main() -> Html eff Console + Fetch {
+    log("only the console is used");
+    handle {
+        quiet()
+    } with Analytics {
+        track(event) => resume(()),
+    };
+    Html {
+        Body {}
+    }
+}
+
+"#);
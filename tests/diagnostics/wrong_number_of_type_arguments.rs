@@ -0,0 +1,14 @@
+#[rustfmt::skip::macros(setup_failing)]
+
+crate::setup_failing!(wrong_number_of_type_arguments; r#"
+
+fn first<T>(xs: List, fallback: T<String>) -> Option<T, T> {
+    let names: List<String<Int>> = xs;
+    first(xs, fallback)
+}
+
+fn main() -> Html {
+    Html {}
+}
+
+"#);
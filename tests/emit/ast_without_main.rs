@@ -0,0 +1,9 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(ast_without_main, Ast; r#"
+
+fn greet(name: String) -> String {
+    name
+}
+
+"#);
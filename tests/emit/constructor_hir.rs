@@ -0,0 +1,19 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(constructor_hir, Hir; r#"
+
+fn describe(answer: Option<Bool>) -> String {
+    match answer {
+        Some(true) => "yes",
+        Some(_) => "no",
+        None => "not asked",
+    }
+}
+
+fn main() -> Html {
+    Html {
+        Paragraph(describe(None)),
+    }
+}
+
+"#);
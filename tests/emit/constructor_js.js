@@ -0,0 +1,2 @@
+function* fn$describe(answer,){return yield* (function*(){const $match=answer;if($match.tag==="Option::Some"&&$match.fields[0].tag==="Option::Some"&&$match.fields[0].fields[0]==="yes"){return "agreed";}else{if($match.tag==="Option::Some"&&$match.fields[0].tag==="Option::Some"){const other=$match.fields[0].fields[0];return other;}else{if($match.tag==="Option::Some"&&$match.fields[0].tag==="Option::None"){return "skipped";}else{if($match.tag==="Option::None"){return "not asked";}}}}})();}function* fn$main(){const answer={tag:"Option::Some",fields:[{tag:"Option::None",fields:[],},],};yield {ty:"std::console::Console::log",args:[yield* fn$describe(answer,),],};}
+const main_call=fn$main();let $value=undefined;while(true){const {done,value:eff,}=main_call.next($value,);if(done){break;}if(eff.ty==="std::console::Console::log"){$value=console.log(eff.args[0],);}}
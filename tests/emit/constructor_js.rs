@@ -0,0 +1,22 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(constructor_js, Js; r#"
+
+fn describe(answer: Option<Option<String>>) -> String {
+    match answer {
+        Some(Some("yes")) => "agreed",
+        Some(Some(other)) => other,
+        Some(None) => "skipped",
+        None => "not asked",
+    }
+}
+
+fn main() -> Html eff Console {
+    let answer = Some(None);
+    log(describe(answer));
+    Html {
+        Paragraph(describe(answer)),
+    }
+}
+
+"#);
@@ -0,0 +1,2 @@
+function* fn$countdown(n,){return yield* (function*(){const $match=n;if($match===0){return 0;}else{return yield* fn$countdown(0,);}})();}function* fn$greet(name,){const name$1=name;yield {ty:"Logger::log",args:[name$1,],};return name$1;}function* fn$main(){const count=yield* fn$countdown(3,);const greeting=yield* (function*(){const $handled=(function*(){return yield* fn$greet("Ferris",);})();function* $resume($value,){const {done:$done,value:$eff,}=$handled.next($value,);if($done){return $eff;}if($eff.ty==="Logger::log"){const message=$eff.args[0];const resume=$resume;return yield* resume(yield {ty:"std::console::Console::log",args:[message,],},);}return yield* $resume(yield $eff,);}return yield* $resume();})();}
+const main_call=fn$main();let $value=undefined;while(true){const {done,value:eff,}=main_call.next($value,);if(done){break;}if(eff.ty==="std::console::Console::log"){$value=console.log(eff.args[0],);}}
@@ -0,0 +1,36 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(js, Js; r#"
+
+effect Logger {
+    fn log(message: String) -> ();
+}
+
+fn countdown(n: Int) -> Int {
+    match n {
+        0 => 0,
+        _ => countdown(0),
+    }
+}
+
+fn greet(name: String) -> String eff Logger {
+    let name = name;
+    Logger::log(name);
+    name
+}
+
+fn main() -> Html eff Console {
+    let count: Int = countdown(3);
+    let greeting = handle {
+        greet("Ferris")
+    } with Logger {
+        log(message) => resume(std::console::log(message)),
+    };
+    Html {
+        Body {
+            Paragraph(if true { "yes" } else { "no" }),
+        }
+    }
+}
+
+"#);
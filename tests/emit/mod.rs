@@ -0,0 +1,9 @@
+pub mod ast_without_main;
+pub mod call_graph_dot;
+pub mod call_graph_json;
+pub mod constructor_hir;
+pub mod constructor_js;
+pub mod hir;
+pub mod js;
+pub mod recovered_ast;
+pub mod unchecked_hir;
@@ -0,0 +1,14 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(recovered_ast, Ast; r#"
+
+fn greet( -> String {
+    "Hello"
+}
+
+fn main() -> Html {
+    let = "missing name";
+    Html {}
+}
+
+"#);
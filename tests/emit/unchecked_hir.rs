@@ -0,0 +1,18 @@
+#[rustfmt::skip::macros(setup_emit)]
+
+crate::setup_emit!(unchecked_hir, Hir; r#"
+
+fn greet(name: String) -> String {
+    name
+}
+
+fn main() -> Html {
+    let greeting: Int = greet(1);
+    Html {
+        Body {
+            Paragraph(greting),
+        }
+    }
+}
+
+"#);
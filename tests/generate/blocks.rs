@@ -0,0 +1,33 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(blocks; r#"
+
+fn title() -> String eff Console {
+    let title = {
+        let greeting = "Hello";
+        log(greeting);
+        greeting
+    };
+    {
+        let title = "shadowed";
+        log(title);
+    }
+    title
+}
+
+fn main() -> Html eff Console {
+    log({ title() });
+
+    {
+        Html {
+            Body {
+                Paragraph({
+                    let name = "Hello";
+                    name
+                })
+            }
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,25 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(components; r#"
+
+fn greet(name: String) -> String eff Console {
+    log(name);
+    name
+}
+
+fn card(title: String, body: String) -> Html {
+    Body {
+        Paragraph(title),
+        Paragraph(body)
+    }
+}
+
+fn main() -> Html eff Console {
+    greet("World");
+
+    Html {
+        card("Hello", "World")
+    }
+}
+
+"#);
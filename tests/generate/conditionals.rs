@@ -0,0 +1,34 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(conditionals; r#"
+
+fn greeting(signed_in: Bool) -> Html {
+    if signed_in {
+        Paragraph("Welcome back!")
+    } else {
+        Paragraph("Please sign in")
+    }
+}
+
+fn main() -> Html eff Console {
+    let signed_in = true;
+    let count = if signed_in { 1 } else { 0 };
+    if signed_in {
+        log("signed in");
+    } else if false {
+        log("never");
+    } else {
+        log("signed out");
+    }
+
+    Html {
+        Body {
+            greeting(signed_in),
+            greeting(false),
+            if false { Paragraph("hidden") },
+            if signed_in { Paragraph(count) } else { Paragraph("none") }
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,37 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(constructor_patterns; r#"
+
+fn find(name: String) -> Option<String> {
+    match name {
+        "Ferris" => Some("crab"),
+        _ => None,
+    }
+}
+
+fn describe(animal: Option<String>) -> String {
+    match animal {
+        Some("crab") => "a crab",
+        Some(other) => other,
+        None => "nobody",
+    }
+}
+
+fn main() -> Html {
+    let nested = Some(Some(true));
+
+    Html {
+        Body {
+            Paragraph(describe(find("Ferris"))),
+            Paragraph(describe(find("Corro"))),
+            Paragraph(describe(Some("an octopus"))),
+            match nested {
+                Some(Some(true)) => Paragraph("deeply true"),
+                Some(_) => Paragraph("shallow"),
+                None => Paragraph("empty"),
+            }
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,15 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(effect_rows; r#"
+
+fn main() -> Html eff Fetch + Console {
+    log("loading posts");
+    get("/posts");
+    Html {
+        Body {
+            Paragraph("Posts")
+        }
+    }
+}
+
+"#);
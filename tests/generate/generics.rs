@@ -0,0 +1,23 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(generics; r#"
+
+fn labelled<T>(value: T, label: String) -> Html {
+    Paragraph(label)
+}
+
+fn id<T>(value: T) -> T {
+    value
+}
+
+fn main() -> Html {
+    Html {
+        Body {
+            labelled(1, "A number"),
+            labelled("text", "Some text"),
+            id(Paragraph(id("Passed through")))
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,98 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(handled_values; r#"
+
+effect Fail {
+    fn fail(reason: String) -> ();
+}
+
+effect Ask {
+    fn ask(question: String) -> String;
+}
+
+effect Choose {
+    fn choose() -> Bool;
+}
+
+fn greeting() -> String eff Ask {
+    let name = ask("name");
+    let title = ask("title");
+    match title {
+        "" => name,
+        title => title,
+    }
+}
+
+fn main() -> Html {
+    // Never resuming stops the handled block, the clause decides what it evaluates to
+    let outcome = handle {
+        fail("stopped");
+        "finished"
+    } with Fail {
+        fail(reason) => reason,
+    };
+
+    // Every operation the handled block performs goes to the same handler
+    let name = handle {
+        greeting()
+    } with Ask {
+        ask(question) => match question {
+            "name" => resume("Ada"),
+            _ => resume(""),
+        },
+    };
+
+    // Operations performed to the outer handler before and after resuming the inner one are each
+    // only resumed once
+    let answer = handle {
+        handle {
+            let greeting = ask("greeting");
+            let loud = choose();
+            if loud {
+                ask("loud")
+            } else {
+                greeting
+            }
+        } with Choose {
+            choose() => {
+                let choice = ask("choice");
+                resume(true)
+            },
+        }
+    } with Ask {
+        ask(question) => resume(question),
+    };
+
+    // Resuming more than once continues the handled block from the same place each time
+    let chosen = handle {
+        if choose() {
+            "yes"
+        } else {
+            "no"
+        }
+    } with Choose {
+        choose() => {
+            let yes = resume(true);
+            resume(false)
+        },
+    };
+
+    // Effects from std that are handled don't need the page to perform them
+    let visitor = handle {
+        prompt("Name?")
+    } with Dialog {
+        prompt(question) => resume("Ferris"),
+    };
+
+    Html {
+        Body {
+            Paragraph(outcome),
+            Paragraph(name),
+            Paragraph(answer),
+            Paragraph(chosen),
+            Paragraph(visitor)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,46 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(handlers; r#"
+
+effect Logger {
+    fn log(msg: String) -> ();
+}
+
+effect Fail {
+    fn fail(reason: String) -> ();
+}
+
+fn greet(name: String) -> () eff Logger {
+    Logger::log(name);
+}
+
+fn main() -> Html eff Console {
+    // Redirects the logger to the console, anything else is still performed by the page
+    handle {
+        greet("Ada");
+        std::console::log("passed through");
+        Logger::log("done");
+    } with Logger {
+        log(msg) => {
+            std::console::log(msg);
+            resume(())
+        },
+    }
+
+    // Never resuming stops the handled block where it performed the operation
+    let outcome = handle {
+        fail("stopped");
+        "finished"
+    } with Fail {
+        fail(reason) => reason,
+    };
+    std::console::log(outcome);
+
+    Html {
+        Body {
+            Paragraph("Handled")
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,25 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(lazy_lets; r#"
+
+/// Keeps asking until it gets an answer, which could go on forever if it were inlined
+fn ask(question: String) -> String eff Dialog {
+    let answer = prompt(question);
+    match answer {
+        "" => ask(question),
+        answer => answer,
+    }
+}
+
+fn main() -> Html eff Dialog {
+    let name = ask("What's your name?");
+    let unused = ask("Anything else?");
+
+    Html {
+        Body {
+            Paragraph("Welcome")
+        }
+    }
+}
+
+"#);
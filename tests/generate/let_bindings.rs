@@ -0,0 +1,22 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(let_bindings; r#"
+
+fn shout(message: String) -> String {
+    let loud = message;
+    loud
+}
+
+fn main() -> Html eff Console {
+    let greeting = "Hello";
+    let greeting: String = shout(greeting);
+    log(greeting);
+
+    Html {
+        Body {
+            Paragraph(greeting)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,29 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(literals; r#"
+
+fn ints(a: Int, b: Int, c: Int, d: Int, e: Int) -> () eff Console {
+    log("ints");
+}
+
+fn floats(a: Float, b: Float, c: Float, d: Float, e: Float) -> () eff Console {
+    log("floats");
+}
+
+fn bools(a: Bool, b: Bool) -> () eff Console {
+    log("bools");
+}
+
+fn main() -> Html eff Console {
+    ints(42, -7, 9007199254740991, 9007199254740993, -9223372036854775808);
+    floats(0.5, 6.02e23, 1e-9, 1e400, -1e400);
+    bools(true, false);
+
+    Html {
+        Body {
+            Paragraph(1e400)
+        }
+    }
+}
+
+"#);
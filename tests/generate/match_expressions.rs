@@ -0,0 +1,36 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(match_expressions; r#"
+
+fn describe(count: Int) -> String {
+    match count {
+        0 => "none",
+        1 => "one",
+        _ => "many",
+    }
+}
+
+fn main() -> Html eff Console {
+    let count = 2;
+    let plenty = true;
+    match describe(count) {
+        "none" => log("nothing to see"),
+        other => {
+            log("found");
+            log(other);
+        },
+    }
+
+    Html {
+        Body {
+            Paragraph(describe(0)),
+            Paragraph(describe(count)),
+            match plenty {
+                true => Paragraph("plenty"),
+                false => Paragraph("few"),
+            }
+        }
+    }
+}
+
+"#);
@@ -1,2 +1,26 @@
+pub mod blocks;
+pub mod components;
+pub mod conditionals;
 pub mod console;
+pub mod constructor_patterns;
+pub mod effect_rows;
+pub mod generics;
+pub mod handled_values;
+pub mod handlers;
 pub mod hello_world;
+pub mod lazy_lets;
+pub mod let_bindings;
+pub mod literals;
+pub mod match_expressions;
+pub mod number_formatting;
+pub mod paths;
+pub mod reserved_names;
+pub mod resumption_values;
+pub mod returned_effects;
+pub mod scripts_without_body;
+pub mod scripts_without_html;
+pub mod shadowed_functions;
+pub mod shadowing;
+pub mod strings;
+pub mod unit_values;
+pub mod user_effects;
@@ -0,0 +1,24 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(number_formatting; r#"
+
+fn main() -> Html {
+    Html {
+        Body {
+            Paragraph(1e21),
+            Paragraph(1e20),
+            Paragraph(123456789012345680000.5),
+            Paragraph(1e-7),
+            Paragraph(0.000001),
+            Paragraph(-2.5e-8),
+            Paragraph(1.5e300),
+            Paragraph(100.0),
+            Paragraph(0.1),
+            Paragraph(-0.0),
+            Paragraph(9007199254740993),
+            Paragraph(-9223372036854775808)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,20 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(paths; r#"
+
+fn Paragraph(text: String) -> std::html::Html {
+    std::html::Paragraph("Hello!")
+}
+
+fn main() -> Html eff std::console::Console {
+    std::console::log("rendering");
+    Html {
+        Body {
+            Paragraph("From the file"),
+            crate::Paragraph("Also from the file"),
+            std::html::Paragraph("From std")
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,27 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(reserved_names; r#"
+
+fn new(this: String) -> String eff Console {
+    let undefined = this;
+    let undefined = undefined;
+    log(undefined);
+    this
+}
+
+fn main_call(console: String) -> () eff Console {
+    log(new(console));
+}
+
+fn main() -> Html eff Console {
+    let class = "reserved";
+    main_call(class);
+
+    Html {
+        Body {
+            Paragraph("Hello")
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,34 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(resumption_values; r#"
+
+effect Config {
+    fn lookup(key: String) -> String;
+}
+
+fn greeting() -> String eff Config {
+    let name = Config::lookup("name");
+    name
+}
+
+fn main() -> Html eff Console + Dialog {
+    // The page resumes with whatever the visitor typed in
+    let name: String = prompt("Name?");
+    std::console::log(name);
+
+    // Handlers resume with the output of the operation
+    let configured = handle {
+        greeting()
+    } with Config {
+        lookup(key) => resume(key),
+    };
+    std::console::log(configured);
+
+    Html {
+        Body {
+            Paragraph("Welcome")
+        }
+    }
+}
+
+"#);
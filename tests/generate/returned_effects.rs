@@ -0,0 +1,20 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(returned_effects; r#"
+
+fn main() -> Html eff Console {
+    log("before");
+    {
+        log("returned");
+        Html {
+            Body {
+                if true {
+                    log("side");
+                    Paragraph("hi")
+                }
+            }
+        }
+    }
+}
+
+"#);
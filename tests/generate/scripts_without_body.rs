@@ -0,0 +1,10 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(scripts_without_body; r#"
+
+fn main() -> Html eff Console {
+    log("starting");
+    Html {}
+}
+
+"#);
@@ -0,0 +1,12 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(scripts_without_html; r#"
+
+fn main() -> Html eff Console {
+    log("starting");
+    Body {
+        Paragraph("Hello")
+    }
+}
+
+"#);
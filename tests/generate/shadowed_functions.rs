@@ -0,0 +1,21 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(shadowed_functions; r#"
+
+fn greet() -> String {
+    "Hello"
+}
+
+fn main() -> Html eff Console {
+    let greet = "shadowed";
+    log(crate::greet());
+    log(greet);
+
+    Html {
+        Body {
+            Paragraph(greet)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,23 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(shadowing; r#"
+
+// Shares its name with the one from std, which it wraps
+fn Paragraph(text: String) -> Html {
+    std::html::Paragraph(text)
+}
+
+fn main() -> Html eff Console {
+    let log = "a variable named log";
+    std::console::log(log);
+    let log = "shadowed";
+    std::console::log(log);
+
+    Html {
+        Body {
+            Paragraph(log)
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,20 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(strings; r##"
+
+fn main() -> Html eff Console {
+    log("a \"quoted\"\tword\nand </script> can't escape");
+    log(r#"C:\raw "path""#);
+
+    Html {
+        Body {
+            Paragraph("Fish & <chips> \u{1F41F}"),
+            Paragraph("""
+                First line
+                  indented line
+                """)
+        }
+    }
+}
+
+"##);
@@ -0,0 +1,18 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(unit_values; r#"
+
+fn empty() -> () {}
+
+fn main() -> Html {
+    Html {
+        Body {
+            Paragraph("Units are left out"),
+            empty(),
+            { let x = "a"; },
+            if true { let y = "b"; } else { () }
+        }
+    }
+}
+
+"#);
@@ -0,0 +1,39 @@
+#[rustfmt::skip::macros(setup)]
+
+crate::setup!(user_effects; r#"
+
+/// Keeps track of what visitors do
+effect Analytics {
+    fn track(event: String) -> ();
+}
+
+/// Its operation is what `log` refers to in this file, the one from std needs its full path
+effect Logger {
+    fn log(msg: String) -> ();
+}
+
+fn tracked(title: String) -> Html eff Analytics + Logger {
+    track("render");
+    Analytics::track(title);
+    crate::Logger::log("rendered");
+    Paragraph(title)
+}
+
+fn main() -> Html eff Console {
+    std::console::log("starting");
+    handle {
+        handle {
+            Html {
+                Body {
+                    tracked("Home")
+                }
+            }
+        } with Analytics {
+            track(event) => resume(()),
+        }
+    } with Logger {
+        log(msg) => resume(()),
+    }
+}
+
+"#);
@@ -1,3 +1,5 @@
 mod setup;
 
+mod diagnostics;
+mod emit;
 mod generate;
@@ -1,61 +1,152 @@
+use std::path::Path;
+
+use pretty_assertions::Comparison;
+
+/// Compares the output of a test against the snapshot file next to it which has the same name but
+/// with the given extension, creating the snapshot if it doesn't exist yet
+pub fn assert_snapshot(test_file: &str, extension: &str, output: &str) {
+    // If the EFF_OVERRIDE_SNAPSHOTS env variable is at all set then we will override even
+    // if the snapshot files contain invalid outputs, which happens often during development
+    let override_snapshots = std::env::var("EFF_OVERRIDE_SNAPSHOTS").ok().is_some();
+
+    let snapshot_file_path = match std::env::current_dir() {
+        Ok(current_dir) => current_dir.join(Path::new(test_file).with_extension(extension)),
+        Err(error) => panic!("Error while detecting current dir: {error}"),
+    };
+    let snapshot_file_name = snapshot_file_path
+        .file_name()
+        .expect("file is derived from file!() macro and has a valid filename")
+        .to_str()
+        .expect("test files names to be valid utf8");
+
+    let snapshot = match std::fs::read_to_string(&snapshot_file_path) {
+        Ok(snapshot) => Some(snapshot),
+        Err(_) if override_snapshots => None,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => {
+            panic!("Error while opening snapshot file {snapshot_file_name}: {error}")
+        }
+    };
+
+    match (snapshot, override_snapshots) {
+        // If the snapshot matches we are done!
+        (Some(snapshot), _) if snapshot.eq(output) => {
+            return;
+        }
+        // If they don't match, display the pretty diff and crash and we don't wanna override
+        (Some(snapshot), false) => {
+            eprintln!("{}", Comparison::new(&snapshot, output));
+            panic!("Snapshot mismatched, to override set env var EFF_OVERRIDE_SNAPSHOTS to 1");
+        }
+        // If they don't match and we are allowed to override, get write access to file
+        (_, true) | (None, _) => {}
+    };
+
+    dbg!(&snapshot_file_path);
+
+    match std::fs::write(&snapshot_file_path, output) {
+        Ok(_) => {}
+        Err(error) => {
+            panic!("Error while writing to snapshot file {snapshot_file_name}: {error}")
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! setup {
     ($test_name:ident; $code:expr) => {
-        use std::path::Path;
+        #[test]
+        fn $test_name() {
+            let output = match effectful::compile($code) {
+                Ok(compiled) if compiled.warnings.is_empty() => compiled.html,
+                Ok(compiled) => panic!(
+                    "Compilation produced warnings:\n{}",
+                    effectful::render_diagnostics("main.eff", $code, &compiled.warnings)
+                ),
+                Err(diagnostics) => panic!(
+                    "Compilation failed:\n{}",
+                    effectful::render_diagnostics("main.eff", $code, &diagnostics)
+                ),
+            };
 
-        use pretty_assertions::Comparison;
+            $crate::setup::assert_snapshot(file!(), "html", &output);
+        }
+    };
+}
 
+/// Like [setup] but for code that should fail to compile, snapshotting the rendered diagnostics
+#[macro_export]
+macro_rules! setup_failing {
+    ($test_name:ident; $code:expr) => {
         #[test]
         fn $test_name() {
-            // If the EFF_OVERRIDE_SNAPSHOTS env variable is at all set then we will override even
-            // if the .ast files contain invalid ASTs, which happens often during parser development
-            let override_snapshots = std::env::var("EFF_OVERRIDE_SNAPSHOTS").ok().is_some();
-
-            let snapshot_file_path = match std::env::current_dir() {
-                Ok(current_dir) => current_dir.join(Path::new(file!()).with_extension("html")),
-                Err(error) => panic!("Error while detecting current dir: {error}"),
+            let diagnostics = match effectful::compile($code) {
+                Ok(compiled) => panic!(
+                    "Compilation was expected to fail but produced:\n{}",
+                    compiled.html
+                ),
+                Err(diagnostics) => diagnostics,
             };
-            let snapshot_file_name = snapshot_file_path
-                .file_name()
-                .expect("file is derived from file!() macro and has a valid filename")
-                .to_str()
-                .expect("test files names to be valid utf8");
-
-            let snapshot = match std::fs::read_to_string(&snapshot_file_path) {
-                Ok(snapshot) => Some(snapshot),
-                Err(_) if override_snapshots => None,
-                Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
-                Err(error) => {
-                    panic!("Error while opening snapshot file {snapshot_file_name}: {error}")
-                }
+            let output = effectful::render_diagnostics("main.eff", $code, &diagnostics);
+
+            $crate::setup::assert_snapshot(file!(), "txt", &output);
+        }
+    };
+}
+
+/// Like [setup] but for code that compiles with warnings, snapshotting the rendered warnings
+#[macro_export]
+macro_rules! setup_warnings {
+    ($test_name:ident; $code:expr) => {
+        #[test]
+        fn $test_name() {
+            let warnings = match effectful::compile($code) {
+                Ok(compiled) => compiled.warnings,
+                Err(diagnostics) => panic!(
+                    "Compilation failed:\n{}",
+                    effectful::render_diagnostics("main.eff", $code, &diagnostics)
+                ),
             };
+            let output = effectful::render_diagnostics("main.eff", $code, &warnings);
 
-            let output = effectful::compile($code);
+            $crate::setup::assert_snapshot(file!(), "txt", &output);
+        }
+    };
+}
 
-            match (snapshot, override_snapshots) {
-                // If the snapshot matches we are done!
-                (Some(snapshot), _) if snapshot.eq(&output) => {
-                    return;
+/// Like [setup] but snapshots one of the other outputs, in a file with that output's extension
+///
+/// The file only goes as far through compiling as the output needs, like it does when compiling
+/// from the command line
+#[macro_export]
+macro_rules! setup_emit {
+    ($test_name:ident, $emit:ident; $code:expr) => {
+        #[test]
+        fn $test_name() {
+            let emit = effectful::Emit::$emit;
+            let failed = |diagnostics: Vec<diagnostics::Diagnostic>| -> ! {
+                panic!(
+                    "Compilation failed:\n{}",
+                    effectful::render_diagnostics("main.eff", $code, &diagnostics)
+                )
+            };
+
+            let output = match (emit.stage(), effectful::parse($code)) {
+                // What could be recovered is written out even when there are syntax errors
+                (effectful::Stage::Parsed, (Some(ast), _)) => emit.from_ast(&ast),
+                (effectful::Stage::Lowered, (Some(ast), errors)) if errors.is_empty() => {
+                    emit.from_hir(&effectful::lower(&ast).0)
                 }
-                // If they don't match, display the pretty diff and crash and we don't wanna override
-                (Some(snapshot), false) => {
-                    eprintln!("{}", Comparison::new(&snapshot, &output));
-                    panic!(
-                        "Snapshot mismatched, to override set env var EFF_OVERRIDE_SNAPSHOTS to 1"
-                    );
+                (effectful::Stage::Parsed | effectful::Stage::Lowered, (_, errors)) => {
+                    failed(errors)
                 }
-                // If they don't match and we are allowed to override, get write access to file
-                (_, true) | (None, _) => {}
+                (effectful::Stage::Compiled, _) => match effectful::compile($code) {
+                    Ok(compiled) => compiled.emit(emit),
+                    Err(diagnostics) => failed(diagnostics),
+                },
             };
 
-            dbg!(&snapshot_file_path);
-
-            match std::fs::write(&snapshot_file_path, &output) {
-                Ok(_) => {}
-                Err(error) => {
-                    panic!("Error while writing to snapshot file {snapshot_file_name}: {error}")
-                }
-            }
+            $crate::setup::assert_snapshot(file!(), emit.extension(), &output);
         }
     };
 }